@@ -0,0 +1,46 @@
+//! Benchmarks the generator and solution of every part of every day, using the puzzle inputs
+//! in `input/2022`.
+//!
+//! Usage: `cargo bench --bench solvers -- [FILTER] [--save-baseline NAME] [--baseline NAME]`
+//!
+//! Every benchmark is named `day<DAY>/parse<PART>` or `day<DAY>/part<PART>`. The filter is a
+//! regular expression, so include the delimiter to select a single day: `day1/` matches day 1,
+//! while `day1` also matches days 10 to 19.
+
+use advent_of_code_2022::runner::input_path;
+use advent_of_code_2022::solver::Part;
+use advent_of_code_2022::solvers;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::hint::black_box;
+
+fn bench_solvers(c: &mut Criterion) {
+    for solver in solvers() {
+        let day = solver.day();
+        let Ok(input) = fs::read_to_string(input_path(day)) else {
+            continue;
+        };
+
+        let mut group = c.benchmark_group(format!("day{}", day));
+        for part in Part::VALUES {
+            group.bench_function(format!("parse{}", part), |b| {
+                b.iter(|| solver.parse(part, black_box(&input)))
+            });
+
+            let Ok(parsed) = solver.parse(part, &input) else {
+                eprintln!(
+                    "day{}/part{}: skipped, the input could not be parsed",
+                    day, part
+                );
+                continue;
+            };
+            group.bench_function(format!("part{}", part), |b| {
+                b.iter(|| solver.solve(part, black_box(&parsed)))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_solvers);
+criterion_main!(benches);
@@ -1,48 +1,84 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// The total calories carried by a single elf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfTotal {
+    /// The 0-based position of the elf's inventory in the puzzle input.
+    pub index: usize,
+    pub calories: usize,
+}
+
+// Returns the `n` elves carrying the most calories, starting with the most. Elves carrying the
+// same number of calories are listed in their original order.
+//
+// Only the best `n` elves seen so far are kept, in a min-heap with the worst of them on top, so
+// this takes O(len log n) instead of sorting all the totals.
+fn top_n(inventories: &[Vec<usize>], n: usize) -> Vec<ElfTotal> {
+    let mut heap = BinaryHeap::with_capacity(n.min(inventories.len()) + 1);
+    for (index, inventory) in inventories.iter().enumerate() {
+        let calories = inventory.iter().sum::<usize>();
+        // Among elves with the same total, the one that comes later is worse.
+        heap.push(Reverse((calories, Reverse(index))));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((calories, Reverse(index)))| ElfTotal { index, calories })
+        .collect()
+}
+
+/// Parses a calorie inventory list and returns the `n` elves carrying the most calories, with
+/// the most first. Pass `usize::MAX` to rank all the elves.
+pub fn calorie_report(input: &str, n: usize) -> Result<Vec<ElfTotal>, ParseError> {
+    Ok(top_n(&parse_input(input)?, n))
+}
 
 #[aoc_generator(day1)]
-fn parse_input(input: &str) -> Vec<Vec<usize>> {
+fn parse_input(input: &str) -> Result<Vec<Vec<usize>>, ParseError> {
+    let input = normalise(input);
     let mut inventories = vec![];
     let mut inventory = vec![];
 
-    for line in input.lines() {
+    for (i, line) in input.lines().enumerate() {
         if line.is_empty() {
             inventories.push(inventory);
             inventory = vec![];
         } else {
-            inventory.push(line.parse::<usize>().unwrap());
+            let calories = line
+                .parse::<usize>()
+                .map_err(|_| ParseError::new(1, i + 1, 1, line, "invalid calorie count"))?;
+            inventory.push(calories);
         }
     }
     inventories.push(inventory);
-    inventories
+    Ok(inventories)
 }
 
 #[aoc(day1, part1)]
 fn part1(inventories: &[Vec<usize>]) -> usize {
-    // Sum up all inventories and return the largest one.
-    inventories
-        .iter()
-        .map(|inventory| inventory.iter().sum())
-        .max()
-        .unwrap()
+    top_n(inventories, 1).iter().map(|elf| elf.calories).sum()
 }
 
 #[aoc(day1, part2)]
 fn part2(inventories: &[Vec<usize>]) -> usize {
-    let mut inventories = inventories
-        .iter()
-        .map(|inventory| inventory.iter().sum())
-        .collect::<Vec<usize>>();
-
-    inventories.sort();
-    inventories.reverse();
+    top_n(inventories, 3).iter().map(|elf| elf.calories).sum()
+}
 
-    inventories.iter().take(3).sum()
+pub fn solver() -> DaySolver {
+    DaySolver::new(1, parse_input, part1, parse_input, part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -55,18 +91,66 @@ mod tests {
             vec![10000],
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("1000\n\n20x0\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(1, 3, 1, "20x0", "invalid calorie count"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_top_n() {
+        let input = parse_input(get_test_input()).unwrap();
+        let elf = |index, calories| ElfTotal { index, calories };
+        assert_eq!(vec![elf(3, 24000)], top_n(&input, 1));
+        assert_eq!(
+            vec![elf(3, 24000), elf(2, 11000), elf(4, 10000)],
+            top_n(&input, 3)
+        );
+        assert_eq!(5, top_n(&input, usize::MAX).len());
+        assert_eq!(vec![] as Vec<ElfTotal>, top_n(&input, 0));
+
+        // Elves with the same total keep their original order.
+        let input = vec![vec![1], vec![3], vec![2, 1], vec![3], vec![1, 2]];
+        assert_eq!(vec![elf(1, 3), elf(2, 3), elf(3, 3)], top_n(&input, 3));
+    }
+
+    #[test]
+    fn test_calorie_report() {
+        let report = calorie_report(get_test_input(), usize::MAX).unwrap();
+        let indexes = report.iter().map(|elf| elf.index).collect::<Vec<usize>>();
+        assert_eq!(vec![3, 2, 4, 0, 1], indexes);
+        assert_eq!(6000, report[3].calories);
+
+        let error = calorie_report("1000\nx\n", 1).unwrap_err();
+        assert_eq!(
+            (2, "invalid calorie count"),
+            (error.line, error.reason.as_str())
+        );
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(24000, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(45000, part2(&input));
     }
 
@@ -1,3 +1,8 @@
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::ocr::{recognise, OcrError};
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashMap;
 
@@ -63,30 +68,39 @@ impl Machine {
         self.pc += 1;
     }
 
-    fn row(&self) -> String {
-        let mut row = str::repeat(".", 40);
-        for i in self.registers.x - 1..self.registers.x + 2 {
-            if (0..40).contains(&i) {
-                row.replace_range(i as usize..i as usize + 1, "#");
-            }
-        }
-        row
+    // The sprite is 3 pixels wide and centered on the X register.
+    fn sprite_covers(&self, x: usize) -> bool {
+        (x as isize - self.registers.x).abs() <= 1
     }
 }
 
 #[aoc_generator(day10)]
-fn parse_input(input: &str) -> Vec<Instruction> {
+fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let input = normalise(input);
     input
         .lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-            let opcode = match parts.next().unwrap() {
+        .enumerate()
+        .map(|(i, line)| {
+            let error = |column, reason| ParseError::new(10, i + 1, column, line, reason);
+            let (opcode, operand) = match line.split_once(' ') {
+                Some((opcode, operand)) => (opcode, Some(operand)),
+                None => (line, None),
+            };
+            let opcode = match opcode {
                 "noop" => Opcode::Noop,
                 "addx" => Opcode::Addx,
-                _ => panic!("Unknown opcode"),
+                _ => return Err(error(1, "unknown opcode")),
+            };
+            // Only the addx instruction takes an operand.
+            let operand = match (opcode, operand) {
+                (Opcode::Noop, None) => None,
+                (Opcode::Addx, Some(operand)) => {
+                    Some(operand.parse().map_err(|_| error(6, "invalid operand"))?)
+                }
+                (Opcode::Noop, Some(_)) => return Err(error(6, "unexpected operand")),
+                (Opcode::Addx, None) => return Err(error(5, "missing operand")),
             };
-            let operand = parts.next().map(|s| s.parse().unwrap());
-            Instruction { opcode, operand }
+            Ok(Instruction { opcode, operand })
         })
         .collect()
 }
@@ -109,36 +123,41 @@ fn part1(motions: &[Instruction]) -> isize {
 }
 
 #[aoc(day10, part2)]
-fn part2(motions: &[Instruction]) -> String {
+fn part2(motions: &[Instruction]) -> Result<String, OcrError> {
+    recognise(&draw(motions))
+}
+
+// Runs the program and returns the image that is drawn on the CRT.
+fn draw(motions: &[Instruction]) -> Grid<bool> {
     let mut machine = Machine::new(motions.to_vec());
-    let mut pixels = String::new();
+    let mut crt = Grid::new(40, 6, false);
 
     while machine.pc < machine.program.len() {
-        let row = machine.row();
-        let current_cycle = machine.clock;
+        // The CRT draws one pixel per cycle, row by row. The sprite position only changes after
+        // the instruction has completed. Ignore any cycles after the last row.
+        let cycles = machine.program[machine.pc].opcode.cycles();
+        for cycle in machine.clock..machine.clock + cycles {
+            let (x, y) = (cycle % crt.width(), cycle / crt.width());
+            let lit = machine.sprite_covers(x);
+            if let Some(pixel) = crt.get_mut(x, y) {
+                *pixel = lit;
+            }
+        }
 
         machine.step();
-        let next_cycle = machine.clock;
-
-        for cycle in current_cycle..next_cycle {
-            let pos = cycle % 40;
-            pixels.push(row.chars().nth(pos).unwrap());
-        }
     }
 
-    // Split the long string of pixels into rows of 40 pixels. Not so easy in Rust o.O
-    pixels
-        .chars()
-        .collect::<Vec<char>>()
-        .chunks(40)
-        .map(|c| c.iter().collect())
-        .collect::<Vec<String>>()
-        .join("\n")
+    crt
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(10, parse_input, part1, parse_input, part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -730,18 +749,41 @@ mod tests {
             },
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("noop\njmp 4\n").unwrap_err();
+        assert_eq!(ParseError::new(10, 2, 1, "jmp 4", "unknown opcode"), error);
+
+        let error = parse_input("addx x\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(10, 1, 6, "addx x", "invalid operand"),
+            error
+        );
+
+        let error = parse_input("addx\n").unwrap_err();
+        assert_eq!(ParseError::new(10, 1, 5, "addx", "missing operand"), error);
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(13140, part1(&input));
     }
 
     #[test]
-    fn part2_example() {
-        let input = parse_input(get_test_input());
+    fn test_draw() {
+        let input = parse_input(get_test_input()).unwrap();
         let expected = indoc! {"
             ##..##..##..##..##..##..##..##..##..##..
             ###...###...###...###...###...###...###.
@@ -749,7 +791,20 @@ mod tests {
             #####.....#####.....#####.....#####.....
             ######......######......######......####
             #######.......#######.......#######....."};
-        assert_eq!(expected, part2(&input));
+        assert_eq!(
+            expected,
+            draw(&input).render(|&lit| if lit { '#' } else { '.' })
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        // The example program does not draw any letters.
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(
+            Err(OcrError::UnrecognisedGlyphs((0..8).collect())),
+            part2(&input)
+        );
     }
 
     fn get_test_input<'a>() -> &'a str {
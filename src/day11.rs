@@ -0,0 +1,392 @@
+use crate::error::ParseError;
+use crate::input::{normalise, paragraphs};
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Old,
+    Value(u64),
+}
+
+impl Operand {
+    fn evaluate(&self, old: u64) -> u64 {
+        match self {
+            Operand::Old => old,
+            Operand::Value(value) => *value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Multiply,
+}
+
+// The expression that computes the new worry level, e.g. `old * 19`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Operation {
+    left: Operand,
+    operator: Operator,
+    right: Operand,
+}
+
+impl Operation {
+    fn apply(&self, old: u64) -> u64 {
+        let (left, right) = (self.left.evaluate(old), self.right.evaluate(old));
+        match self.operator {
+            Operator::Add => left + right,
+            Operator::Multiply => left * right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Monkey {
+    items: Vec<u64>,
+    operation: Operation,
+    divisor: u64,
+    if_true: usize,
+    if_false: usize,
+}
+
+impl Monkey {
+    fn target(&self, worry: u64) -> usize {
+        if worry % self.divisor == 0 {
+            self.if_true
+        } else {
+            self.if_false
+        }
+    }
+}
+
+// A single line of a monkey block, used to report errors at the right position.
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn error(&self, column: usize, reason: &str) -> ParseError {
+        ParseError::new(11, self.number, column, self.text, reason)
+    }
+
+    // Strips the indentation and the given label from the line, and returns the remaining value
+    // together with its 1-based column.
+    fn value(&self, label: &str) -> Result<(&'a str, usize), ParseError> {
+        let indent = self.text.len() - self.text.trim_start().len();
+        let value = self.text[indent..]
+            .strip_prefix(label)
+            .ok_or_else(|| self.error(indent + 1, &format!("expected \"{}\"", label)))?;
+        Ok((value, indent + label.len() + 1))
+    }
+
+    fn number<T: std::str::FromStr>(&self, label: &str, reason: &str) -> Result<T, ParseError> {
+        let (value, column) = self.value(label)?;
+        value.parse().map_err(|_| self.error(column, reason))
+    }
+}
+
+fn parse_operand(operand: &str) -> Option<Operand> {
+    match operand {
+        "old" => Some(Operand::Old),
+        _ => operand.parse().ok().map(Operand::Value),
+    }
+}
+
+fn parse_monkey(index: usize, lines: &[Line]) -> Result<Monkey, ParseError> {
+    let [header, items, operation, test, if_true, if_false] = lines else {
+        let last = lines.last().unwrap();
+        return Err(last.error(1, "expected a monkey description of six lines"));
+    };
+
+    let number: usize = header
+        .value("Monkey ")?
+        .0
+        .strip_suffix(':')
+        .and_then(|number| number.parse().ok())
+        .ok_or_else(|| header.error(8, "invalid monkey number"))?;
+    if number != index {
+        return Err(header.error(8, "unexpected monkey number"));
+    }
+
+    // The items are separated by a comma, keep track of the column of each item.
+    let (list, mut column) = items.value("Starting items: ")?;
+    let items = list
+        .split(", ")
+        .map(|item| {
+            let worry = item
+                .parse()
+                .map_err(|_| items.error(column, "invalid item"))?;
+            column += item.len() + 2;
+            Ok(worry)
+        })
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+
+    let (expression, column) = operation.value("Operation: new = ")?;
+    let operation = match expression.split(' ').collect::<Vec<&str>>()[..] {
+        [left, operator, right] => {
+            let operand_error = || operation.error(column, "invalid operand");
+            Operation {
+                left: parse_operand(left).ok_or_else(operand_error)?,
+                operator: match operator {
+                    "+" => Operator::Add,
+                    "*" => Operator::Multiply,
+                    _ => return Err(operation.error(column + left.len() + 1, "invalid operator")),
+                },
+                right: parse_operand(right).ok_or_else(|| {
+                    operation.error(column + left.len() + operator.len() + 2, "invalid operand")
+                })?,
+            }
+        }
+        _ => return Err(operation.error(column, "invalid operation")),
+    };
+
+    let divisor = test.number("Test: divisible by ", "invalid divisor")?;
+    if divisor == 0 {
+        return Err(test.error(test.value("Test: divisible by ")?.1, "invalid divisor"));
+    }
+
+    Ok(Monkey {
+        items,
+        operation,
+        divisor,
+        if_true: if_true.number("If true: throw to monkey ", "invalid monkey number")?,
+        if_false: if_false.number("If false: throw to monkey ", "invalid monkey number")?,
+    })
+}
+
+#[aoc_generator(day11)]
+fn parse_input(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let input = normalise(input);
+    let blocks = paragraphs(&input)
+        .into_iter()
+        .map(|(first_line, block)| {
+            block
+                .lines()
+                .enumerate()
+                .map(|(i, text)| Line {
+                    number: first_line + i + 1,
+                    text,
+                })
+                .collect::<Vec<Line>>()
+        })
+        .collect::<Vec<Vec<Line>>>();
+
+    let monkeys = blocks
+        .iter()
+        .enumerate()
+        .map(|(index, lines)| parse_monkey(index, lines))
+        .collect::<Result<Vec<Monkey>, ParseError>>()?;
+
+    // Every monkey must throw to another monkey that exists.
+    for (index, (monkey, lines)) in monkeys.iter().zip(&blocks).enumerate() {
+        for (target, line) in [(monkey.if_true, &lines[4]), (monkey.if_false, &lines[5])] {
+            if target == index || target >= monkeys.len() {
+                let column = line.text.rfind(' ').unwrap() + 2;
+                return Err(line.error(column, "invalid throw target"));
+            }
+        }
+    }
+
+    Ok(monkeys)
+}
+
+// Plays the given number of rounds and returns the level of monkey business, i.e. the product of
+// the number of items inspected by the two most active monkeys.
+fn play<F>(monkeys: &[Monkey], rounds: usize, relief: F) -> usize
+where
+    F: Fn(u64) -> u64,
+{
+    let mut monkeys = monkeys.to_vec();
+    let mut inspections = vec![0; monkeys.len()];
+
+    for _ in 0..rounds {
+        for i in 0..monkeys.len() {
+            let items = std::mem::take(&mut monkeys[i].items);
+            inspections[i] += items.len();
+            for worry in items {
+                let worry = relief(monkeys[i].operation.apply(worry));
+                let target = monkeys[i].target(worry);
+                monkeys[target].items.push(worry);
+            }
+        }
+    }
+
+    inspections.sort_unstable_by(|a, b| b.cmp(a));
+    inspections.iter().take(2).product()
+}
+
+#[aoc(day11, part1)]
+fn part1(monkeys: &[Monkey]) -> usize {
+    play(monkeys, 20, |worry| worry / 3)
+}
+
+#[aoc(day11, part2)]
+fn part2(monkeys: &[Monkey]) -> usize {
+    // The monkeys only check whether the worry levels are divisible by their divisor, which is
+    // preserved when reducing the worry levels modulo the product of all divisors. This keeps
+    // the worry levels bounded, so they never overflow.
+    let modulus = monkeys.iter().map(|monkey| monkey.divisor).product::<u64>();
+    play(monkeys, 10000, |worry| worry % modulus)
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(11, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let expected = vec![
+            Monkey {
+                items: vec![79, 98],
+                operation: Operation {
+                    left: Operand::Old,
+                    operator: Operator::Multiply,
+                    right: Operand::Value(19),
+                },
+                divisor: 23,
+                if_true: 2,
+                if_false: 3,
+            },
+            Monkey {
+                items: vec![54, 65, 75, 74],
+                operation: Operation {
+                    left: Operand::Old,
+                    operator: Operator::Add,
+                    right: Operand::Value(6),
+                },
+                divisor: 19,
+                if_true: 2,
+                if_false: 0,
+            },
+            Monkey {
+                items: vec![79, 60, 97],
+                operation: Operation {
+                    left: Operand::Old,
+                    operator: Operator::Multiply,
+                    right: Operand::Old,
+                },
+                divisor: 13,
+                if_true: 1,
+                if_false: 3,
+            },
+            Monkey {
+                items: vec![74],
+                operation: Operation {
+                    left: Operand::Old,
+                    operator: Operator::Add,
+                    right: Operand::Value(3),
+                },
+                divisor: 17,
+                if_true: 0,
+                if_false: 1,
+            },
+        ];
+
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("79, 60, 97", "79, x, 97");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(11, 16, 23, "  Starting items: 79, x, 97", "invalid item"),
+            error
+        );
+
+        let input = get_test_input().replace("old + 6", "old - 6");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(11, 10, 24, "  Operation: new = old - 6", "invalid operator"),
+            error
+        );
+
+        let input = get_test_input().replace("Monkey 2:", "Monkey 4:");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(11, 15, 8, "Monkey 4:", "unexpected monkey number"),
+            error
+        );
+
+        let input = get_test_input().replace("divisible by 17", "divisible by 0");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(11, 25, 22, "  Test: divisible by 0", "invalid divisor"),
+            error
+        );
+
+        let input = get_test_input().replace("true: throw to monkey 1", "true: throw to monkey 5");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                11,
+                19,
+                30,
+                "    If true: throw to monkey 5",
+                "invalid throw target"
+            ),
+            error
+        );
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(10605, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(2713310158, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            Monkey 0:
+              Starting items: 79, 98
+              Operation: new = old * 19
+              Test: divisible by 23
+                If true: throw to monkey 2
+                If false: throw to monkey 3
+
+            Monkey 1:
+              Starting items: 54, 65, 75, 74
+              Operation: new = old + 6
+              Test: divisible by 19
+                If true: throw to monkey 2
+                If false: throw to monkey 0
+
+            Monkey 2:
+              Starting items: 79, 60, 97
+              Operation: new = old * old
+              Test: divisible by 13
+                If true: throw to monkey 1
+                If false: throw to monkey 3
+
+            Monkey 3:
+              Starting items: 74
+              Operation: new = old + 3
+              Test: divisible by 17
+                If true: throw to monkey 0
+                If false: throw to monkey 1
+        "}
+    }
+}
@@ -1,118 +1,114 @@
+use crate::error::ParseError;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
-use pathfinding::prelude::{bfs, Matrix};
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
-
-// Implement the From trait for (usize, usize).
-impl<T> From<T> for Coordinate
-where
-    T: Into<(usize, usize)>,
-{
-    fn from(key: T) -> Self {
-        let (y, x) = key.into();
-        Coordinate { x, y }
-    }
-}
+use pathfinding::prelude::bfs;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 struct HeightMap {
-    map: Matrix<u8>,
-    start: Coordinate,
-    dest: Coordinate,
+    map: Grid<u8>,
+    start: Point<usize>,
+    dest: Point<usize>,
 }
 
 #[aoc_generator(day12, part1)]
-fn parse_input_part1(input: &str) -> HeightMap {
-    let mut matrix: Matrix<u8> = Matrix::from_rows(input.lines().map(str::bytes)).unwrap();
-
-    // Find the keys for the start and end points.
-    let start = matrix.keys().find(|k| matrix[*k] == b'S').unwrap();
-    let dest = matrix.keys().find(|k| matrix[*k] == b'E').unwrap();
+fn parse_input_part1(input: &str) -> Result<HeightMap, ParseError> {
+    let input = normalise(input);
+    // The heightmap only contains heights and the start and end markers.
+    let mut map = Grid::parse(12, &input, |c| {
+        (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c as u8)
+    })?;
+
+    // Find the positions of the start and end points.
+    let find = |marker, reason| {
+        let line = input.lines().count() + 1;
+        map.position(|&c| c == marker)
+            .map(Point::from)
+            .ok_or_else(|| ParseError::new(12, line, 1, "", reason))
+    };
+    let start = find(b'S', "missing start position")?;
+    let dest = find(b'E', "missing destination")?;
 
     // We start at height 'a' and end at height 'z'. Overwrite the heights for the start and end
     // points with the appropriate values.
-    matrix[start] = b'a';
-    matrix[dest] = b'z';
+    map[start.to_tuple()] = b'a';
+    map[dest.to_tuple()] = b'z';
 
-    HeightMap {
-        map: matrix,
-        start: start.into(),
-        dest: dest.into(),
-    }
+    Ok(HeightMap { map, start, dest })
 }
 
 #[aoc_generator(day12, part2)]
-fn parse_input_part2(input: &str) -> HeightMap {
+fn parse_input_part2(input: &str) -> Result<HeightMap, ParseError> {
     parse_input_part1(input)
 }
 
 #[aoc(day12, part1)]
-fn part1(height_map: &HeightMap) -> usize {
-    let start = (height_map.start.y, height_map.start.x);
-    let dest = (height_map.dest.y, height_map.dest.x);
-    let matrix = &height_map.map;
+fn part1(height_map: &HeightMap) -> Option<usize> {
+    let start = height_map.start.to_tuple();
+    let dest = height_map.dest.to_tuple();
+    let map = &height_map.map;
     bfs(
         &start,
         // Find all neighbours that are at most 1 meter higher than the current point.
-        |&p| {
-            matrix.neighbours(p, false).filter(move |&q| {
-                let height_diff = (matrix[q] as i32) - (matrix[p] as i32);
+        |&(x, y)| {
+            map.neighbours4(x, y).filter(move |&q| {
+                let height_diff = (map[q] as i32) - (map[(x, y)] as i32);
                 height_diff <= 1
             })
         },
         |&p| p == dest,
     )
-    .unwrap()
-    .len()
-        - 1
+    .map(|path| path.len() - 1)
 }
 
 #[aoc(day12, part2)]
-fn part2(height_map: &HeightMap) -> usize {
+fn part2(height_map: &HeightMap) -> Option<usize> {
     // Walk from the top down to the nearest point at elevation 'a'.
-    let start = (height_map.dest.y, height_map.dest.x);
-    let matrix = &height_map.map;
+    let start = height_map.dest.to_tuple();
+    let map = &height_map.map;
     bfs(
         &start,
         // Find all neighbours that are at most 1 meter lower than the current point.
-        |&p| {
-            matrix.neighbours(p, false).filter(move |&q| {
-                let height_diff = (matrix[q] as i32) - (matrix[p] as i32);
+        |&(x, y)| {
+            map.neighbours4(x, y).filter(move |&q| {
+                let height_diff = (map[q] as i32) - (map[(x, y)] as i32);
                 height_diff >= -1
             })
         },
-        |&p| matrix[p] == b'a',
+        |&p| map[p] == b'a',
     )
-    .unwrap()
-    .len()
-        - 1
+    .map(|path| path.len() - 1)
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(12, parse_input_part1, part1, parse_input_part2, part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
     fn test_parse_input_part1() {
         let expected = HeightMap {
-            map: Matrix::<u8>::from_rows(vec![
-                vec![b'a', b'a', b'b', b'q', b'p', b'o', b'n', b'm'],
-                vec![b'a', b'b', b'c', b'r', b'y', b'x', b'x', b'l'],
-                vec![b'a', b'c', b'c', b's', b'z', b'z', b'x', b'k'],
-                vec![b'a', b'c', b'c', b't', b'u', b'v', b'w', b'j'],
-                vec![b'a', b'b', b'd', b'e', b'f', b'g', b'h', b'i'],
-            ])
-            .unwrap(),
-            start: Coordinate { x: 0, y: 0 },
-            dest: Coordinate { x: 5, y: 2 },
+            map: Grid::from_fn(8, 5, |x, y| {
+                [
+                    b"aabqponm",
+                    b"abcryxxl",
+                    b"accszzxk",
+                    b"acctuvwj",
+                    b"abdefghi",
+                ][y][x]
+            }),
+            start: Point::new(0, 0),
+            dest: Point::new(5, 2),
         };
 
-        assert_eq!(expected, parse_input_part1(get_test_input_part1()));
+        assert_eq!(Ok(expected), parse_input_part1(get_test_input_part1()));
     }
 
     #[test]
@@ -120,16 +116,52 @@ mod tests {
         test_parse_input_part1();
     }
 
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input_part1(get_test_input_part1());
+        for input in variants(get_test_input_part1()) {
+            assert_eq!(expected, parse_input_part1(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input_part1("Sabq\nab1E\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(12, 2, 3, "ab1E", "invalid character"),
+            error
+        );
+
+        let error = parse_input_part1("Sabq\nabE\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(12, 2, 1, "abE", "inconsistent row length"),
+            error
+        );
+
+        let error = parse_input_part1("Sabq\nabcd\n").unwrap_err();
+        assert_eq!(ParseError::new(12, 3, 1, "", "missing destination"), error);
+    }
+
+    #[test]
+    fn test_solve_invalid() {
+        // The destination is surrounded by a cliff.
+        let input = parse_input_part1("Sbcde\nabcbE\n").unwrap();
+        assert_eq!(None, part1(&input));
+        // There is no way down from the top to any square at elevation 'a'.
+        let input = parse_input_part2("Sxyz\nxxyE\n").unwrap();
+        assert_eq!(None, part2(&input));
+    }
+
     #[test]
     fn part1_example() {
-        let input = parse_input_part1(get_test_input_part1());
-        assert_eq!(31, part1(&input));
+        let input = parse_input_part1(get_test_input_part1()).unwrap();
+        assert_eq!(Some(31), part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input_part2(get_test_input_part2());
-        assert_eq!(29, part2(&input));
+        let input = parse_input_part2(get_test_input_part2()).unwrap();
+        assert_eq!(Some(29), part2(&input));
     }
 
     fn get_test_input_part1<'a>() -> &'a str {
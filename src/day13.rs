@@ -0,0 +1,354 @@
+use crate::error::ParseError;
+use crate::input::{normalise, paragraphs};
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+enum Packet {
+    Integer(u32),
+    List(Vec<Packet>),
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(a), Packet::Integer(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a.cmp(b),
+            // An integer is compared to a list as if it were a list containing only that integer.
+            (Packet::Integer(_), Packet::List(b)) => std::slice::from_ref(self).cmp(b.as_slice()),
+            (Packet::List(a), Packet::Integer(_)) => a.as_slice().cmp(std::slice::from_ref(other)),
+        }
+    }
+}
+
+// Packets are equal when they are in the right order both ways, e.g. `[1]` and `[[1]]`, so that
+// equality agrees with the ordering.
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Integer(value) => write!(f, "{}", value),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+// A recursive descent parser for a single packet. Errors are returned as a 1-based column and a
+// reason.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(line: &'a str) -> Result<Packet, (usize, &'static str)> {
+        let mut parser = Parser {
+            bytes: line.as_bytes(),
+            position: 0,
+        };
+        if parser.peek() != Some(b'[') {
+            return Err((1, "expected a list"));
+        }
+        let packet = parser.packet()?;
+        if parser.position < parser.bytes.len() {
+            return Err((parser.position + 1, "unexpected character after the packet"));
+        }
+        Ok(packet)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn packet(&mut self) -> Result<Packet, (usize, &'static str)> {
+        match self.peek() {
+            Some(b'[') => self.list(),
+            Some(c) if c.is_ascii_digit() => self.integer(),
+            Some(_) => Err((self.position + 1, "expected an integer or a list")),
+            None => Err((self.position + 1, "unexpected end of the packet")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, (usize, &'static str)> {
+        // Skip the opening bracket.
+        self.position += 1;
+        let mut packets = vec![];
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Packet::List(packets));
+        }
+        loop {
+            packets.push(self.packet()?);
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Packet::List(packets));
+                }
+                Some(_) => {
+                    return Err((self.position + 1, "expected a comma or a closing bracket"))
+                }
+                None => return Err((self.position + 1, "unexpected end of the packet")),
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Packet, (usize, &'static str)> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        // The slice only contains ASCII digits, so it is valid UTF-8.
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .unwrap()
+            .parse()
+            .map(Packet::Integer)
+            .map_err(|_| (start + 1, "invalid integer"))
+    }
+}
+
+#[aoc_generator(day13)]
+fn parse_input(input: &str) -> Result<Vec<(Packet, Packet)>, ParseError> {
+    let input = normalise(input);
+    paragraphs(&input)
+        .into_iter()
+        .map(|(first_line, pair)| {
+            let packets = pair
+                .lines()
+                .enumerate()
+                .map(|(i, line)| {
+                    Parser::parse(line).map_err(|(column, reason)| {
+                        ParseError::new(13, first_line + i + 1, column, line, reason)
+                    })
+                })
+                .collect::<Result<Vec<Packet>, ParseError>>()?;
+            match <[Packet; 2]>::try_from(packets) {
+                Ok([left, right]) => Ok((left, right)),
+                Err(_) => {
+                    let line = pair.lines().next().unwrap();
+                    let reason = "expected a pair of packets";
+                    Err(ParseError::new(13, first_line + 1, 1, line, reason))
+                }
+            }
+        })
+        .collect()
+}
+
+#[aoc(day13, part1)]
+fn part1(pairs: &[(Packet, Packet)]) -> usize {
+    pairs
+        .iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
+        .sum()
+}
+
+#[aoc(day13, part2)]
+fn part2(pairs: &[(Packet, Packet)]) -> usize {
+    let dividers = [
+        Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]),
+        Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]),
+    ];
+    let mut packets = pairs
+        .iter()
+        .flat_map(|(left, right)| [left, right])
+        .chain(dividers.iter())
+        .collect::<Vec<&Packet>>();
+    packets.sort();
+
+    dividers
+        .iter()
+        .map(|divider| {
+            packets
+                .iter()
+                .position(|&packet| packet == divider)
+                .unwrap()
+                + 1
+        })
+        .product()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(13, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    fn packet(line: &str) -> Packet {
+        Parser::parse(line).unwrap()
+    }
+
+    #[test]
+    fn test_parse_input() {
+        use Packet::{Integer, List};
+
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(8, input.len());
+        assert_eq!(
+            (
+                List(vec![
+                    List(vec![Integer(1)]),
+                    List(vec![Integer(2), Integer(3), Integer(4)]),
+                ]),
+                List(vec![List(vec![Integer(1)]), Integer(4)]),
+            ),
+            input[1]
+        );
+        assert_eq!(
+            (
+                List(vec![List(vec![List(vec![])])]),
+                List(vec![List(vec![])])
+            ),
+            input[6]
+        );
+
+        // Every packet is printed exactly as it appears in the input.
+        let lines = get_test_input()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<&str>>();
+        let packets = input
+            .iter()
+            .flat_map(|(left, right)| [left.to_string(), right.to_string()])
+            .collect::<Vec<String>>();
+        assert_eq!(lines, packets);
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("[[1],4]", "[[1],4");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(13, 5, 7, "[[1],4", "unexpected end of the packet"),
+            error
+        );
+
+        let input = get_test_input().replace("[9]", "[9;]");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(13, 7, 3, "[9;]", "expected a comma or a closing bracket"),
+            error
+        );
+
+        let input = get_test_input().replace("[[4,4],4,4]", "[[4,,4],4,4]");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(13, 10, 5, "[[4,,4],4,4]", "expected an integer or a list"),
+            error
+        );
+
+        let input = get_test_input().replace("[3]", "[3]]");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(13, 17, 4, "[3]]", "unexpected character after the packet"),
+            error
+        );
+
+        let input = get_test_input().replace("[7,7,7]\n", "");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(13, 13, 1, "[7,7,7,7]", "expected a pair of packets"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        // Integers are compared by value.
+        assert!(packet("[1,1,3,1,1]") < packet("[1,1,5,1,1]"));
+        // An integer is promoted to a list when it is compared to a list.
+        assert!(packet("[[1],[2,3,4]]") < packet("[[1],4]"));
+        assert!(packet("[9]") > packet("[[8,7,6]]"));
+        assert_eq!(Ordering::Equal, packet("[1]").cmp(&packet("[[1]]")));
+        assert_eq!(packet("[1]"), packet("[[1]]"));
+        assert_eq!(Ordering::Equal, packet("[[[5]]]").cmp(&packet("[5]")));
+        assert_eq!(packet("[[[5]]]"), packet("[5]"));
+        assert_ne!(packet("[1]"), packet("[[1],1]"));
+        assert!(packet("[[5],2]") < packet("[5,3]"));
+        assert!(packet("[[]]") < packet("[0]"));
+        // The shorter list comes first if all its elements are equal.
+        assert!(packet("[[4,4],4,4]") < packet("[[4,4],4,4,4]"));
+        assert!(packet("[7,7,7,7]") > packet("[7,7,7]"));
+        assert!(packet("[]") < packet("[3]"));
+        assert!(packet("[[[]]]") > packet("[[]]"));
+        // Multi-digit integers are parsed as a single integer.
+        assert!(packet("[10]") > packet("[9]"));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(13, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(140, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            [1,1,3,1,1]
+            [1,1,5,1,1]
+
+            [[1],[2,3,4]]
+            [[1],4]
+
+            [9]
+            [[8,7,6]]
+
+            [[4,4],4,4]
+            [[4,4],4,4,4]
+
+            [7,7,7,7]
+            [7,7,7]
+
+            []
+            [3]
+
+            [[[]]]
+            [[]]
+
+            [1,[2,[3,[4,[5,6,7]]]],8,9]
+            [1,[2,[3,[4,[5,6,0]]]],8,9]
+        "}
+    }
+}
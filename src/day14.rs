@@ -0,0 +1,354 @@
+use crate::error::ParseError;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::fmt;
+
+// The position where the sand enters the cave.
+const SOURCE: Point<usize> = Point::new(500, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
+// A path of rock, made of horizontal and vertical segments between consecutive points.
+type Path = Vec<Point<usize>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cave {
+    grid: Grid<Tile>,
+    // The x coordinate of the leftmost column of the grid.
+    left: usize,
+    // The y coordinate of the lowest rock.
+    bottom: usize,
+}
+
+impl Cave {
+    fn new(paths: &[Path]) -> Self {
+        let points = || paths.iter().flatten();
+        let bottom = points().map(|p| p.y).max().unwrap_or(0);
+        // The sand spreads at most one column per row, so it never goes further sideways than
+        // the depth of the floor, two rows below the lowest rock.
+        let floor = bottom + 2;
+        let left = points()
+            .map(|p| p.x)
+            .chain([SOURCE.x.saturating_sub(floor)])
+            .min()
+            .unwrap();
+        let right = points()
+            .map(|p| p.x)
+            .chain([SOURCE.x + floor])
+            .max()
+            .unwrap();
+
+        let mut cave = Self {
+            grid: Grid::new(right - left + 1, floor + 1, Tile::Air),
+            left,
+            bottom,
+        };
+        for path in paths {
+            for segment in path.windows(2) {
+                let (a, b) = (segment[0], segment[1]);
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    for y in a.y.min(b.y)..=a.y.max(b.y) {
+                        cave.grid[(x - left, y)] = Tile::Rock;
+                    }
+                }
+            }
+            // A path consisting of a single point is a single block of rock.
+            if let [point] = path[..] {
+                cave.grid[(point.x - left, point.y)] = Tile::Rock;
+            }
+        }
+        cave
+    }
+
+    fn get(&self, point: Point<usize>) -> Tile {
+        self.grid[(point.x - self.left, point.y)]
+    }
+
+    fn set(&mut self, point: Point<usize>, tile: Tile) {
+        self.grid[(point.x - self.left, point.y)] = tile;
+    }
+
+    // The positions a grain of sand tries to move to, in order of preference.
+    fn moves(point: Point<usize>) -> [Point<usize>; 3] {
+        let y = point.y + 1;
+        [
+            Point::new(point.x, y),
+            Point::new(point.x - 1, y),
+            Point::new(point.x + 1, y),
+        ]
+    }
+
+    // Drops sand until it starts flowing into the abyss below the lowest rock, and returns the
+    // number of grains that came to rest.
+    //
+    // Every grain follows the path of the previous grain until the position where that one came
+    // to rest, so the path is kept and the next grain starts from the last position before it.
+    fn pour(&mut self) -> usize {
+        let mut path = vec![SOURCE];
+        let mut count = 0;
+
+        while let Some(&point) = path.last() {
+            if point.y >= self.bottom {
+                break;
+            }
+            match Self::moves(point)
+                .into_iter()
+                .find(|&next| self.get(next) == Tile::Air)
+            {
+                Some(next) => path.push(next),
+                None => {
+                    self.set(point, Tile::Sand);
+                    count += 1;
+                    path.pop();
+                }
+            }
+        }
+
+        count
+    }
+
+    // Fills the cave with sand on top of the floor, until the source is blocked, and returns the
+    // number of grains that came to rest.
+    //
+    // With a floor, every position that sand can reach is eventually filled. A position is
+    // reachable if it is not rock and one of the three positions above it is reachable, so the
+    // cave can be filled row by row instead of grain by grain.
+    fn fill(&mut self) -> usize {
+        let floor = self.grid.height() - 1;
+        for x in 0..self.grid.width() {
+            self.grid[(x, floor)] = Tile::Rock;
+        }
+
+        self.set(SOURCE, Tile::Sand);
+        let mut count = 1;
+        for y in 1..floor {
+            for x in 1..self.grid.width() - 1 {
+                let reachable = (x - 1..=x + 1).any(|x| self.grid[(x, y - 1)] == Tile::Sand);
+                if reachable && self.grid[(x, y)] == Tile::Air {
+                    self.grid[(x, y)] = Tile::Sand;
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+}
+
+// Renders the part of the cave that contains rock or sand, in the same format as the puzzle
+// description. Useful for debugging, e.g. `println!("{}", cave)`.
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let occupied = self
+            .grid
+            .positions()
+            .filter(|&p| self.grid[p] != Tile::Air)
+            .chain([(SOURCE.x - self.left, SOURCE.y)]);
+        let (mut min_x, mut max_x, mut max_y) = (usize::MAX, 0, 0);
+        for (x, y) in occupied {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        for y in 0..=max_y {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in min_x..=max_x {
+                let c = match self.grid[(x, y)] {
+                    Tile::Air if (x + self.left, y) == SOURCE.to_tuple() => '+',
+                    Tile::Air => '.',
+                    Tile::Rock => '#',
+                    Tile::Sand => 'o',
+                };
+                write!(f, "{}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[aoc_generator(day14)]
+fn parse_input(input: &str) -> Result<Vec<Path>, ParseError> {
+    let input = normalise(input);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let error = |column, reason| ParseError::new(14, i + 1, column, line, reason);
+            let mut column = 1;
+            let mut path: Path = vec![];
+            for point in line.split(" -> ") {
+                let coordinates = point
+                    .split_once(',')
+                    .and_then(|(x, y)| Some(Point::new(x.parse().ok()?, y.parse().ok()?)))
+                    .ok_or_else(|| error(column, "invalid point"))?;
+                if let Some(previous) = path.last() {
+                    if previous.x != coordinates.x && previous.y != coordinates.y {
+                        return Err(error(column, "diagonal rock segment"));
+                    }
+                }
+                path.push(coordinates);
+                column += point.len() + 4;
+            }
+            Ok(path)
+        })
+        .collect()
+}
+
+#[aoc(day14, part1)]
+fn part1(paths: &[Path]) -> usize {
+    Cave::new(paths).pour()
+}
+
+#[aoc(day14, part2)]
+fn part2(paths: &[Path]) -> usize {
+    Cave::new(paths).fill()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(14, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let expected = vec![
+            vec![Point::new(498, 4), Point::new(498, 6), Point::new(496, 6)],
+            vec![
+                Point::new(503, 4),
+                Point::new(502, 4),
+                Point::new(502, 9),
+                Point::new(494, 9),
+            ],
+        ];
+
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("502,9", "502;9");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                14,
+                2,
+                19,
+                "503,4 -> 502,4 -> 502;9 -> 494,9",
+                "invalid point"
+            ),
+            error
+        );
+
+        let input = get_test_input().replace("496,6", "496,7");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                14,
+                1,
+                19,
+                "498,4 -> 498,6 -> 496,7",
+                "diagonal rock segment"
+            ),
+            error
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let paths = parse_input(get_test_input()).unwrap();
+        let mut cave = Cave::new(&paths);
+        let expected = indoc! {"
+            ......+...
+            ..........
+            ..........
+            ..........
+            ....#...##
+            ....#...#.
+            ..###...#.
+            ........#.
+            ........#.
+            #########.
+        "};
+        assert_eq!(expected.trim_end(), cave.to_string());
+
+        cave.pour();
+        let expected = indoc! {"
+            ......+...
+            ..........
+            ......o...
+            .....ooo..
+            ....#ooo##
+            ...o#ooo#.
+            ..###ooo#.
+            ....oooo#.
+            .o.ooooo#.
+            #########.
+        "};
+        assert_eq!(expected.trim_end(), cave.to_string());
+    }
+
+    #[test]
+    fn test_render_floor() {
+        let paths = parse_input(get_test_input()).unwrap();
+        let mut cave = Cave::new(&paths);
+        cave.fill();
+        let expected = indoc! {"
+            ...........o...........
+            ..........ooo..........
+            .........ooooo.........
+            ........ooooooo........
+            .......oo#ooo##o.......
+            ......ooo#ooo#ooo......
+            .....oo###ooo#oooo.....
+            ....oooo.oooo#ooooo....
+            ...oooooooooo#oooooo...
+            ..ooo#########ooooooo..
+            .ooooo.......ooooooooo.
+            #######################
+        "};
+        assert_eq!(expected.trim_end(), cave.to_string());
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(24, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(93, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            498,4 -> 498,6 -> 496,6
+            503,4 -> 502,4 -> 502,9 -> 494,9
+        "}
+    }
+}
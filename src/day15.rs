@@ -0,0 +1,341 @@
+use crate::error::ParseError;
+use crate::geometry::Point;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use regex::Regex;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+// The row to check in part 1, and the maximum coordinate of the search area in part 2. They are
+// not part of the input, and differ between the real puzzle input and the example.
+const ROW: i64 = 2_000_000;
+const BOUND: i64 = 4_000_000;
+const EXAMPLE_ROW: i64 = 10;
+const EXAMPLE_BOUND: i64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Sensor {
+    position: Point<i64>,
+    beacon: Point<i64>,
+}
+
+impl Sensor {
+    // The distance to the closest beacon. There can be no other beacon within this distance.
+    fn radius(&self) -> i64 {
+        self.position.manhattan(&self.beacon) as i64
+    }
+
+    fn covers(&self, point: &Point<i64>) -> bool {
+        self.position.manhattan(point) as i64 <= self.radius()
+    }
+
+    // Returns the range of x coordinates that the sensor covers on the given row.
+    fn coverage(&self, y: i64) -> Option<RangeInclusive<i64>> {
+        let half_width = self.radius() - (self.position.y - y).abs();
+        (half_width >= 0).then(|| self.position.x - half_width..=self.position.x + half_width)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Scan {
+    sensors: Vec<Sensor>,
+    // The row to check in part 1.
+    row: i64,
+    // The maximum x and y coordinate of the search area in part 2.
+    bound: i64,
+}
+
+#[aoc_generator(day15)]
+fn parse_input(input: &str) -> Result<Scan, ParseError> {
+    let input = normalise(input);
+    let re =
+        Regex::new(r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$")
+            .unwrap();
+    let sensors = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| ParseError::new(15, i + 1, 1, l, "invalid sensor report"))?;
+            // The coordinates are matched by the regex, so they can only fail to parse if they
+            // are out of range.
+            let coordinate = |n: usize| {
+                let m = caps.get(n).unwrap();
+                m.as_str()
+                    .parse()
+                    .map_err(|_| ParseError::new(15, i + 1, m.start() + 1, l, "invalid coordinate"))
+            };
+            Ok(Sensor {
+                position: Point::new(coordinate(1)?, coordinate(2)?),
+                beacon: Point::new(coordinate(3)?, coordinate(4)?),
+            })
+        })
+        .collect::<Result<Vec<Sensor>, ParseError>>()?;
+
+    // The input is taken to be the example if all the sensors lie within the example's search
+    // area. In the real input the sensors are spread out over millions of positions.
+    let example_area = 0..=EXAMPLE_BOUND;
+    let is_example = sensors.iter().all(|sensor| {
+        example_area.contains(&sensor.position.x) && example_area.contains(&sensor.position.y)
+    });
+    let (row, bound) = if is_example {
+        (EXAMPLE_ROW, EXAMPLE_BOUND)
+    } else {
+        (ROW, BOUND)
+    };
+    Ok(Scan {
+        sensors,
+        row,
+        bound,
+    })
+}
+
+// Returns the ranges of x coordinates covered by the sensors on the given row, sorted and merged
+// so they neither overlap nor touch.
+fn merged_coverage(sensors: &[Sensor], y: i64) -> Vec<RangeInclusive<i64>> {
+    let mut ranges = sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage(y))
+        .collect::<Vec<RangeInclusive<i64>>>();
+    ranges.sort_by_key(|range| *range.start());
+
+    let mut merged: Vec<RangeInclusive<i64>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *range.start() <= last.end() + 1 => {
+                *last = *last.start()..=*last.end().max(range.end());
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// Counts the positions on the given row where a beacon cannot be present.
+fn count_excluded(sensors: &[Sensor], y: i64) -> usize {
+    let ranges = merged_coverage(sensors, y);
+    let covered = ranges
+        .iter()
+        .map(|range| (range.end() - range.start() + 1) as usize)
+        .sum::<usize>();
+    // The known beacons are obviously not excluded.
+    let beacons = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.y == y && ranges.iter().any(|range| range.contains(&beacon.x)))
+        .collect::<HashSet<Point<i64>>>();
+    covered - beacons.len()
+}
+
+// Finds the only position within the search area that is not covered by any sensor.
+//
+// Since there is only one such position, it must lie just outside the edge of the area covered
+// by some sensors, or on the edge of the search area. The edges of the sensors are diagonal
+// lines, so the position is one of the intersections of these lines with each other or with the
+// edges of the search area. That is far fewer positions to check than the whole area.
+fn find_distress_beacon(sensors: &[Sensor], bound: i64) -> Option<Point<i64>> {
+    // Ascending lines are described by `y - x`, descending lines by `y + x`. Start with the
+    // diagonals of the search area, so its corners are checked too.
+    let mut ascending = vec![0];
+    let mut descending = vec![bound];
+    for sensor in sensors {
+        let (x, y) = (sensor.position.x, sensor.position.y);
+        let distance = sensor.radius() + 1;
+        ascending.extend([y - x - distance, y - x + distance]);
+        descending.extend([y + x - distance, y + x + distance]);
+    }
+
+    let intersections = ascending.iter().flat_map(|&a| {
+        descending
+            .iter()
+            .filter(move |&&b| (a + b) % 2 == 0)
+            .map(move |&b| Point::new((b - a) / 2, (a + b) / 2))
+    });
+    let edges = ascending
+        .iter()
+        .flat_map(|&a| {
+            [
+                Point::new(0, a),
+                Point::new(bound, bound + a),
+                Point::new(-a, 0),
+                Point::new(bound - a, bound),
+            ]
+        })
+        .chain(descending.iter().flat_map(|&b| {
+            [
+                Point::new(0, b),
+                Point::new(bound, b - bound),
+                Point::new(b, 0),
+                Point::new(b - bound, bound),
+            ]
+        }));
+
+    intersections.chain(edges).find(|point| {
+        (0..=bound).contains(&point.x)
+            && (0..=bound).contains(&point.y)
+            && !sensors.iter().any(|sensor| sensor.covers(point))
+    })
+}
+
+fn tuning_frequency(point: Point<i64>) -> i64 {
+    point.x * 4_000_000 + point.y
+}
+
+#[aoc(day15, part1)]
+fn part1(scan: &Scan) -> usize {
+    count_excluded(&scan.sensors, scan.row)
+}
+
+#[aoc(day15, part2)]
+fn part2(scan: &Scan) -> Option<i64> {
+    find_distress_beacon(&scan.sensors, scan.bound).map(tuning_frequency)
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(15, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use crate::solver::{Answer, Part, Solver};
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!((EXAMPLE_ROW, EXAMPLE_BOUND), (input.row, input.bound));
+        let input = input.sensors;
+        assert_eq!(14, input.len());
+        assert_eq!(
+            Sensor {
+                position: Point::new(2, 18),
+                beacon: Point::new(-2, 15),
+            },
+            input[0]
+        );
+        assert_eq!(
+            Sensor {
+                position: Point::new(20, 1),
+                beacon: Point::new(15, 3),
+            },
+            input[13]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("x=9, y=16", "x=9 y=16");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                15,
+                2,
+                1,
+                "Sensor at x=9 y=16: closest beacon is at x=10, y=16",
+                "invalid sensor report"
+            ),
+            error
+        );
+
+        let input = get_test_input().replace("y=20:", "y=99999999999999999999:");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                15,
+                5,
+                19,
+                "Sensor at x=10, y=99999999999999999999: closest beacon is at x=10, y=16",
+                "invalid coordinate"
+            ),
+            error
+        );
+    }
+
+    #[test]
+    fn test_merged_coverage() {
+        let input = parse_input(get_test_input()).unwrap().sensors;
+        assert_eq!(vec![-2..=24], merged_coverage(&input, 10));
+        assert_eq!(vec![-3..=13, 15..=25], merged_coverage(&input, 11));
+        // Only the sensor at (2, 0) reaches this row, with its top corner.
+        assert_eq!(vec![2..=2], merged_coverage(&input, -10));
+        assert!(merged_coverage(&input, -11).is_empty());
+    }
+
+    #[test]
+    fn test_find_distress_beacon() {
+        let input = parse_input(get_test_input()).unwrap().sensors;
+        assert_eq!(Some(Point::new(14, 11)), find_distress_beacon(&input, 20));
+
+        // The position can also be in a corner of the search area.
+        let sensors = [Sensor {
+            position: Point::new(0, 0),
+            beacon: Point::new(7, 0),
+        }];
+        assert_eq!(Some(Point::new(4, 4)), find_distress_beacon(&sensors, 4));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(26, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(56000011), part2(&input));
+    }
+
+    #[test]
+    fn test_row_and_bound() {
+        // A single sensor outside the example's search area means this is a real input.
+        let input = format!(
+            "{}Sensor at x=3000000, y=5: closest beacon is at x=3000001, y=5\n",
+            get_test_input()
+        );
+        let input = parse_input(&input).unwrap();
+        assert_eq!((ROW, BOUND), (input.row, input.bound));
+
+        // The example is solved through the public solver, without setting anything up.
+        let solver = solver();
+        assert_eq!(
+            Answer::Integer(26),
+            solver.run(Part::One, get_test_input()).unwrap()
+        );
+        assert_eq!(
+            Answer::Integer(56000011),
+            solver.run(Part::Two, get_test_input()).unwrap()
+        );
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+            Sensor at x=9, y=16: closest beacon is at x=10, y=16
+            Sensor at x=13, y=2: closest beacon is at x=15, y=3
+            Sensor at x=12, y=14: closest beacon is at x=10, y=16
+            Sensor at x=10, y=20: closest beacon is at x=10, y=16
+            Sensor at x=14, y=17: closest beacon is at x=10, y=16
+            Sensor at x=8, y=7: closest beacon is at x=2, y=10
+            Sensor at x=2, y=0: closest beacon is at x=2, y=10
+            Sensor at x=0, y=11: closest beacon is at x=2, y=10
+            Sensor at x=20, y=14: closest beacon is at x=25, y=17
+            Sensor at x=17, y=20: closest beacon is at x=21, y=22
+            Sensor at x=16, y=7: closest beacon is at x=15, y=3
+            Sensor at x=14, y=3: closest beacon is at x=15, y=3
+            Sensor at x=20, y=1: closest beacon is at x=15, y=3
+        "}
+    }
+}
@@ -0,0 +1,326 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use pathfinding::prelude::dijkstra_all;
+use regex::Regex;
+use std::collections::HashMap;
+
+const START: &str = "AA";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Valve {
+    name: String,
+    rate: u32,
+    tunnels: Vec<String>,
+}
+
+// The valves with a non-zero flow rate, which are the only ones worth opening, and the travel
+// times between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Network {
+    rates: Vec<u32>,
+    // The number of minutes it takes to walk from one valve to another. The starting valve comes
+    // after the valves with a non-zero flow rate. Unreachable valves have a distance of
+    // `u32::MAX`.
+    distances: Vec<Vec<u32>>,
+}
+
+impl Network {
+    fn new(valves: &[Valve]) -> Self {
+        let indexes = valves
+            .iter()
+            .enumerate()
+            .map(|(i, valve)| (valve.name.as_str(), i))
+            .collect::<HashMap<&str, usize>>();
+        let tunnels = valves
+            .iter()
+            .map(|valve| {
+                valve
+                    .tunnels
+                    .iter()
+                    .map(|name| indexes[name.as_str()])
+                    .collect()
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let mut relevant = (0..valves.len())
+            .filter(|&i| valves[i].rate > 0)
+            .collect::<Vec<usize>>();
+        relevant.push(indexes[START]);
+
+        let distances = relevant
+            .iter()
+            .map(|&from| {
+                let reachable =
+                    dijkstra_all(&from, |&valve| tunnels[valve].iter().map(|&next| (next, 1)));
+                relevant
+                    .iter()
+                    .map(|to| match reachable.get(to) {
+                        _ if *to == from => 0,
+                        Some(&(_, distance)) => distance,
+                        None => u32::MAX,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            rates: relevant[..relevant.len() - 1]
+                .iter()
+                .map(|&i| valves[i].rate)
+                .collect(),
+            distances,
+        }
+    }
+
+    // Returns, for every set of valves, the most pressure that can be released by opening exactly
+    // those valves within the given time. The sets are represented as bitmasks, indexed by the
+    // position of the valve in `rates`.
+    fn best_pressures(&self, time: u32) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.rates.len()];
+        self.explore(self.rates.len(), time, 0, 0, &mut best);
+        best
+    }
+
+    fn explore(&self, valve: usize, time: u32, opened: usize, pressure: u32, best: &mut [u32]) {
+        best[opened] = best[opened].max(pressure);
+        for (next, remaining) in self.moves(valve, time, opened) {
+            let pressure = pressure + self.rates[next] * remaining;
+            self.explore(next, remaining, opened | (1 << next), pressure, best);
+        }
+    }
+
+    // Returns the most pressure that can be released within the given time.
+    //
+    // This only needs the best set of valves, so unlike `best_pressures()` it can skip the
+    // sequences that cannot beat the best one found so far.
+    fn max_pressure(&self, time: u32) -> u32 {
+        let mut best = 0;
+        self.search(self.rates.len(), time, 0, 0, &mut best);
+        best
+    }
+
+    fn search(&self, valve: usize, time: u32, opened: usize, pressure: u32, best: &mut u32) {
+        *best = (*best).max(pressure);
+        if pressure + self.upper_bound(time, opened) <= *best {
+            return;
+        }
+        for (next, remaining) in self.moves(valve, time, opened) {
+            let pressure = pressure + self.rates[next] * remaining;
+            self.search(next, remaining, opened | (1 << next), pressure, best);
+        }
+    }
+
+    // Returns the valves that can still be opened in time, and the time that is left after
+    // opening each of them. Opening a valve takes one minute on top of walking there.
+    fn moves(
+        &self,
+        valve: usize,
+        time: u32,
+        opened: usize,
+    ) -> impl Iterator<Item = (usize, u32)> + '_ {
+        (0..self.rates.len()).filter_map(move |next| {
+            let cost = self.distances[valve][next].saturating_add(1);
+            (opened & (1 << next) == 0 && cost < time).then(|| (next, time - cost))
+        })
+    }
+
+    // An optimistic estimate of the pressure that can still be released, assuming the closed
+    // valves are opened from high to low flow rate, one every two minutes.
+    fn upper_bound(&self, time: u32, opened: usize) -> u32 {
+        let mut rates = (0..self.rates.len())
+            .filter(|valve| opened & (1 << valve) == 0)
+            .map(|valve| self.rates[valve])
+            .collect::<Vec<u32>>();
+        rates.sort_unstable_by(|a, b| b.cmp(a));
+        rates
+            .iter()
+            .zip((0..time).rev().step_by(2))
+            .map(|(rate, remaining)| rate * remaining)
+            .sum()
+    }
+}
+
+#[aoc_generator(day16)]
+fn parse_input(input: &str) -> Result<Vec<Valve>, ParseError> {
+    let input = normalise(input);
+    let re = Regex::new(
+        r"^Valve ([A-Z]{2}) has flow rate=(\d+); tunnels? leads? to valves? ([A-Z]{2}(?:, [A-Z]{2})*)$",
+    )
+    .unwrap();
+    let valves = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| ParseError::new(16, i + 1, 1, l, "invalid valve report"))?;
+            let rate = caps[2]
+                .parse()
+                .map_err(|_| ParseError::new(16, i + 1, 24, l, "invalid flow rate"))?;
+            Ok(Valve {
+                name: caps[1].to_string(),
+                rate,
+                tunnels: caps[3].split(", ").map(String::from).collect(),
+            })
+        })
+        .collect::<Result<Vec<Valve>, ParseError>>()?;
+
+    // Every tunnel must lead to a known valve. The names of the valves are at the end of the
+    // line, each followed by a comma and a space except for the last one.
+    for (i, (valve, line)) in valves.iter().zip(input.lines()).enumerate() {
+        for (j, name) in valve.tunnels.iter().enumerate() {
+            if !valves.iter().any(|valve| valve.name == *name) {
+                let column = line.len() - (valve.tunnels.len() - j) * 4 + 3;
+                return Err(ParseError::new(16, i + 1, column, line, "unknown valve"));
+            }
+        }
+    }
+    if !valves.iter().any(|valve| valve.name == START) {
+        let line = input.lines().count() + 1;
+        return Err(ParseError::new(16, line, 1, "", "missing valve AA"));
+    }
+
+    Ok(valves)
+}
+
+#[aoc(day16, part1)]
+fn part1(valves: &[Valve]) -> u32 {
+    Network::new(valves).max_pressure(30)
+}
+
+#[aoc(day16, part2)]
+fn part2(valves: &[Valve]) -> u32 {
+    let network = Network::new(valves);
+    let mut best = network.best_pressures(26);
+
+    // Extend the results to the best pressure for every set of valves when opening a subset of
+    // them, by adding one valve at a time to the sets that lack it.
+    for valve in 0..network.rates.len() {
+        for opened in 0..best.len() {
+            if opened & (1 << valve) != 0 {
+                best[opened] = best[opened].max(best[opened ^ (1 << valve)]);
+            }
+        }
+    }
+
+    // You and the elephant open disjoint sets of valves, so the elephant gets the best out of
+    // the valves you leave closed.
+    let all = best.len() - 1;
+    (0..best.len())
+        .map(|opened| best[opened] + best[all ^ opened])
+        .max()
+        .unwrap()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(16, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(10, input.len());
+        assert_eq!(
+            Valve {
+                name: "AA".to_string(),
+                rate: 0,
+                tunnels: vec!["DD".to_string(), "II".to_string(), "BB".to_string()],
+            },
+            input[0]
+        );
+        assert_eq!(
+            Valve {
+                name: "HH".to_string(),
+                rate: 22,
+                tunnels: vec!["GG".to_string()],
+            },
+            input[7]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("flow rate=13", "flow rate=-13");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                16,
+                2,
+                1,
+                "Valve BB has flow rate=-13; tunnels lead to valves CC, AA",
+                "invalid valve report"
+            ),
+            error
+        );
+
+        let input = get_test_input().replace("valves CC, AA", "valves CC, XX");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                16,
+                2,
+                55,
+                "Valve BB has flow rate=13; tunnels lead to valves CC, XX",
+                "unknown valve"
+            ),
+            error
+        );
+
+        let input = get_test_input().replace("AA", "ZZ");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(16, 11, 1, "", "missing valve AA"), error);
+    }
+
+    #[test]
+    fn test_network() {
+        let input = parse_input(get_test_input()).unwrap();
+        let network = Network::new(&input);
+        // The valves BB, CC, DD, EE, HH and JJ, followed by AA.
+        assert_eq!(vec![13, 2, 20, 3, 22, 21], network.rates);
+        assert_eq!(vec![1, 2, 1, 2, 5, 2, 0], network.distances[6]);
+        assert_eq!(vec![6, 5, 4, 3, 0, 7, 5], network.distances[4]);
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(1651, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(1707, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+            Valve BB has flow rate=13; tunnels lead to valves CC, AA
+            Valve CC has flow rate=2; tunnels lead to valves DD, BB
+            Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+            Valve EE has flow rate=3; tunnels lead to valves FF, DD
+            Valve FF has flow rate=0; tunnels lead to valves EE, GG
+            Valve GG has flow rate=0; tunnels lead to valves FF, HH
+            Valve HH has flow rate=22; tunnel leads to valve GG
+            Valve II has flow rate=0; tunnels lead to valves AA, JJ
+            Valve JJ has flow rate=21; tunnel leads to valve II
+        "}
+    }
+}
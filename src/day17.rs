@@ -0,0 +1,317 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::HashMap;
+use std::fmt;
+
+const WIDTH: usize = 7;
+
+// The rock shapes in the order they fall, as rows from the bottom up. Every row is a bitmask
+// where the most significant of the seven bits is the leftmost column. The shapes are positioned
+// two units away from the left wall, where they appear.
+const ROCKS: [&[u8]; 5] = [
+    &[0b0011110],
+    &[0b0001000, 0b0011100, 0b0001000],
+    &[0b0011100, 0b0000100, 0b0000100],
+    &[0b0010000, 0b0010000, 0b0010000, 0b0010000],
+    &[0b0011000, 0b0011000],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Jet {
+    Left,
+    Right,
+}
+
+// Everything that determines how the rest of the simulation plays out: the next rock and jet,
+// and the shape of the surface relative to the highest rock.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct State {
+    rock: usize,
+    jet: usize,
+    surface: [usize; WIDTH],
+}
+
+#[derive(Debug, Clone)]
+struct Chamber<'a> {
+    jets: &'a [Jet],
+    // The rows of settled rock from the floor up, as bitmasks like the rocks.
+    rows: Vec<u8>,
+    rocks: usize,
+    jet: usize,
+}
+
+impl<'a> Chamber<'a> {
+    fn new(jets: &'a [Jet]) -> Self {
+        Self {
+            jets,
+            rows: vec![],
+            rocks: 0,
+            jet: 0,
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn collides(&self, rock: &[u8], y: usize) -> bool {
+        rock.iter().enumerate().any(|(i, row)| {
+            self.rows
+                .get(y + i)
+                .is_some_and(|settled| settled & row != 0)
+        })
+    }
+
+    // Drops the next rock until it comes to rest.
+    fn drop_rock(&mut self) {
+        let mut rock = ROCKS[self.rocks % ROCKS.len()].to_vec();
+        let mut y = self.height() + 3;
+
+        loop {
+            let jet = self.jets[self.jet];
+            self.jet = (self.jet + 1) % self.jets.len();
+            let pushed = match jet {
+                Jet::Left if rock.iter().all(|row| row & 0b1000000 == 0) => {
+                    rock.iter().map(|row| row << 1).collect()
+                }
+                Jet::Right if rock.iter().all(|row| row & 0b0000001 == 0) => {
+                    rock.iter().map(|row| row >> 1).collect()
+                }
+                _ => rock.clone(),
+            };
+            if !self.collides(&pushed, y) {
+                rock = pushed;
+            }
+
+            if y == 0 || self.collides(&rock, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        for (i, row) in rock.iter().enumerate() {
+            if y + i == self.rows.len() {
+                self.rows.push(0);
+            }
+            self.rows[y + i] |= row;
+        }
+        self.rocks += 1;
+    }
+
+    fn state(&self) -> State {
+        // The depth of the highest rock in every column, below the top of the tower.
+        let mut surface = [self.height(); WIDTH];
+        for (x, depth) in surface.iter_mut().enumerate() {
+            let mask = 1 << (WIDTH - 1 - x);
+            if let Some(i) = self.rows.iter().rev().position(|row| row & mask != 0) {
+                *depth = i;
+            }
+        }
+        State {
+            rock: self.rocks % ROCKS.len(),
+            jet: self.jet,
+            surface,
+        }
+    }
+}
+
+// Renders the top of the tower in the same format as the puzzle description, for debugging. The
+// precision sets the number of rows, e.g. `println!("{:.20}", chamber)`, and the floor is
+// included if the rows reach down to it. Without a precision, the whole tower is rendered.
+impl fmt::Display for Chamber<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = f.precision().unwrap_or(self.height());
+        for (i, row) in self.rows.iter().rev().take(rows).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "|")?;
+            for x in 0..WIDTH {
+                let c = match row & (1 << (WIDTH - 1 - x)) {
+                    0 => '.',
+                    _ => '#',
+                };
+                write!(f, "{}", c)?;
+            }
+            write!(f, "|")?;
+        }
+        if rows >= self.height() {
+            if self.height() > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "+{}+", "-".repeat(WIDTH))?;
+        }
+        Ok(())
+    }
+}
+
+// Returns the height of the tower after the given number of rocks have fallen.
+//
+// Once the chamber returns to a state it has been in before, the rocks keep falling in the same
+// cycle. The height gained by all the full cycles that remain can be computed directly, and only
+// the rocks after the last full cycle have to be simulated.
+fn tower_height(jets: &[Jet], rocks: usize) -> usize {
+    let mut chamber = Chamber::new(jets);
+    let mut seen: HashMap<State, (usize, usize)> = HashMap::new();
+    let mut skipped_height = None;
+
+    while chamber.rocks < rocks {
+        if skipped_height.is_none() {
+            let state = chamber.state();
+            if let Some((previous_rocks, previous_height)) =
+                seen.insert(state, (chamber.rocks, chamber.height()))
+            {
+                let cycle_length = chamber.rocks - previous_rocks;
+                let cycles = (rocks - chamber.rocks) / cycle_length;
+                skipped_height = Some(cycles * (chamber.height() - previous_height));
+                // Pretend the rocks of the skipped cycles have fallen.
+                chamber.rocks += cycles * cycle_length;
+                continue;
+            }
+        }
+        chamber.drop_rock();
+    }
+
+    chamber.height() + skipped_height.unwrap_or(0)
+}
+
+#[aoc_generator(day17)]
+fn parse_input(input: &str) -> Result<Vec<Jet>, ParseError> {
+    let input = normalise(input);
+    if input.is_empty() {
+        return Err(ParseError::new(17, 1, 1, "", "empty jet pattern"));
+    }
+    let line = input.lines().next().unwrap();
+    if let Some((i, extra)) = input.lines().enumerate().nth(1) {
+        return Err(ParseError::new(17, i + 1, 1, extra, "unexpected line"));
+    }
+    line.chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            _ => Err(ParseError::new(17, 1, i + 1, line, "invalid jet")),
+        })
+        .collect()
+}
+
+#[aoc(day17, part1)]
+fn part1(jets: &[Jet]) -> usize {
+    tower_height(jets, 2022)
+}
+
+#[aoc(day17, part2)]
+fn part2(jets: &[Jet]) -> usize {
+    tower_height(jets, 1_000_000_000_000)
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(17, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(40, input.len());
+        assert_eq!(
+            vec![Jet::Right, Jet::Right, Jet::Right, Jet::Left],
+            input[..4]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input(">><>x<").unwrap_err();
+        assert_eq!(ParseError::new(17, 1, 5, ">><>x<", "invalid jet"), error);
+
+        let error = parse_input(">><\n<<>\n").unwrap_err();
+        assert_eq!(ParseError::new(17, 2, 1, "<<>", "unexpected line"), error);
+
+        let error = parse_input("\n").unwrap_err();
+        assert_eq!(ParseError::new(17, 1, 1, "", "empty jet pattern"), error);
+    }
+
+    #[test]
+    fn test_render() {
+        let jets = parse_input(get_test_input()).unwrap();
+        let mut chamber = Chamber::new(&jets);
+        for _ in 0..10 {
+            chamber.drop_rock();
+        }
+        let expected = indoc! {"
+            |....#..|
+            |....#..|
+            |....##.|
+            |##..##.|
+            |######.|
+            |.###...|
+            |..#....|
+            |.####..|
+            |....##.|
+            |....##.|
+            |....#..|
+            |..#.#..|
+            |..#.#..|
+            |#####..|
+            |..###..|
+            |...#...|
+            |..####.|
+            +-------+
+        "};
+        assert_eq!(17, chamber.height());
+        assert_eq!(expected.trim_end(), format!("{:.20}", chamber));
+        assert_eq!(expected.trim_end(), chamber.to_string());
+        assert_eq!("|....#..|\n|....#..|", format!("{:.2}", chamber));
+        assert_eq!("+-------+", Chamber::new(&jets).to_string());
+    }
+
+    #[test]
+    fn test_tower_height() {
+        // The cycle detection must give the same result as simulating every rock.
+        let jets = parse_input(get_test_input()).unwrap();
+        let mut chamber = Chamber::new(&jets);
+        for rocks in 1..=200 {
+            chamber.drop_rock();
+            assert_eq!(
+                chamber.height(),
+                tower_height(&jets, rocks),
+                "{} rocks",
+                rocks
+            );
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(3068, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(1514285714288, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            >>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>
+        "}
+    }
+}
@@ -0,0 +1,153 @@
+use crate::error::ParseError;
+use crate::geometry::Point3;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use pathfinding::prelude::bfs_reach;
+use std::collections::HashSet;
+
+#[aoc_generator(day18)]
+fn parse_input(input: &str) -> Result<Vec<Point3<isize>>, ParseError> {
+    let input = normalise(input);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let error = |column, reason| ParseError::new(18, i + 1, column, line, reason);
+            let mut column = 1;
+            let coordinates = line
+                .split(',')
+                .map(|coordinate| {
+                    let value = coordinate
+                        .parse()
+                        .map_err(|_| error(column, "invalid coordinate"))?;
+                    column += coordinate.len() + 1;
+                    Ok(value)
+                })
+                .collect::<Result<Vec<isize>, ParseError>>()?;
+            match coordinates[..] {
+                [x, y, z] => Ok(Point3::new(x, y, z)),
+                _ => Err(error(1, "expected three coordinates")),
+            }
+        })
+        .collect()
+}
+
+#[aoc(day18, part1)]
+fn part1(cubes: &[Point3<isize>]) -> usize {
+    let lava = cubes.iter().copied().collect::<HashSet<Point3<isize>>>();
+    // Every side of a cube that does not touch another cube is part of the surface.
+    lava.iter()
+        .flat_map(|cube| cube.neighbours6())
+        .filter(|neighbour| !lava.contains(neighbour))
+        .count()
+}
+
+#[aoc(day18, part2)]
+fn part2(cubes: &[Point3<isize>]) -> usize {
+    let lava = cubes.iter().copied().collect::<HashSet<Point3<isize>>>();
+    let Some((min, max)) = Point3::bounding_box(lava.iter().copied()) else {
+        return 0;
+    };
+
+    // Flood fill the air around the droplet, within a box that leaves a gap of one cube around
+    // it, so the water can flow around every side. Air pockets inside the droplet are never
+    // reached.
+    let margin = Point3::new(1, 1, 1);
+    let (min, max) = (min - margin, max + margin);
+    let outside = bfs_reach(min, |cube| {
+        cube.neighbours6()
+            .filter(|neighbour| neighbour.is_within(&min, &max) && !lava.contains(neighbour))
+            .collect::<Vec<Point3<isize>>>()
+    });
+
+    // Count the sides of the cubes that the water can reach.
+    outside
+        .map(|air| {
+            air.neighbours6()
+                .filter(|neighbour| lava.contains(neighbour))
+                .count()
+        })
+        .sum()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(18, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(13, input.len());
+        assert_eq!(Point3::new(2, 2, 2), input[0]);
+        assert_eq!(Point3::new(2, 3, 5), input[12]);
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("1,2,2", "1,x,2");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(18, 2, 3, "1,x,2", "invalid coordinate"),
+            error
+        );
+
+        let input = get_test_input().replace("3,2,2", "3,2");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(18, 3, 1, "3,2", "expected three coordinates"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_two_cubes() {
+        let cubes = parse_input("1,1,1\n2,1,1\n").unwrap();
+        assert_eq!(10, part1(&cubes));
+        assert_eq!(10, part2(&cubes));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(64, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(58, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            2,2,2
+            1,2,2
+            3,2,2
+            2,1,2
+            2,3,2
+            2,2,1
+            2,2,3
+            2,2,4
+            2,2,6
+            1,2,5
+            3,2,5
+            2,1,5
+            2,3,5
+        "}
+    }
+}
@@ -0,0 +1,251 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use regex::Regex;
+use std::thread;
+
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Blueprint {
+    id: u32,
+    // The amount of ore, clay and obsidian needed to build each type of robot, indexed by the
+    // material the robot collects.
+    costs: [[u32; 3]; 4],
+}
+
+impl Blueprint {
+    // Returns the most geodes that can be opened within the given time.
+    fn max_geodes(&self, time: u32) -> u32 {
+        // A factory can only build one robot per minute, so there is no point in collecting more
+        // of a material per minute than the most expensive robot needs.
+        let mut max_robots = [u32::MAX; 4];
+        for material in [ORE, CLAY, OBSIDIAN] {
+            max_robots[material] = self.costs.iter().map(|cost| cost[material]).max().unwrap();
+        }
+
+        let mut search = Search {
+            blueprint: self,
+            max_robots,
+            best: 0,
+        };
+        search.run(time, [1, 0, 0, 0], [0, 0, 0], 0);
+        search.best
+    }
+}
+
+// A depth first search over the order in which the robots are built.
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    max_robots: [u32; 4],
+    best: u32,
+}
+
+impl Search<'_> {
+    fn run(&mut self, time: u32, robots: [u32; 4], materials: [u32; 3], geodes: u32) {
+        self.best = self.best.max(geodes);
+        // Even building a geode robot every remaining minute cannot beat the best result.
+        if geodes + time * (time - 1) / 2 <= self.best {
+            return;
+        }
+
+        // Instead of simulating minute by minute, skip ahead to the minute when the next robot
+        // can be built. Waiting without building anything afterwards never helps.
+        for robot in [GEODE, OBSIDIAN, CLAY, ORE] {
+            if robots[robot] >= self.max_robots[robot] {
+                continue;
+            }
+            let Some(wait) = self.time_to_build(robot, &robots, &materials) else {
+                continue;
+            };
+            if wait >= time {
+                continue;
+            }
+
+            let remaining = time - wait;
+            let cost = &self.blueprint.costs[robot];
+            let mut materials = materials;
+            for material in [ORE, CLAY, OBSIDIAN] {
+                materials[material] += robots[material] * wait;
+                materials[material] -= cost[material];
+            }
+            if robot == GEODE {
+                // Count all the geodes the robot will open right away.
+                self.run(remaining, robots, materials, geodes + remaining);
+            } else {
+                let mut robots = robots;
+                robots[robot] += 1;
+                self.run(remaining, robots, materials, geodes);
+            }
+        }
+    }
+
+    // Returns the number of minutes until the robot is built, including the minute it takes to
+    // build it, or `None` if no robots collect one of the materials it needs.
+    fn time_to_build(&self, robot: usize, robots: &[u32; 4], materials: &[u32; 3]) -> Option<u32> {
+        let cost = &self.blueprint.costs[robot];
+        let mut wait = 0;
+        for material in [ORE, CLAY, OBSIDIAN] {
+            if cost[material] > materials[material] {
+                if robots[material] == 0 {
+                    return None;
+                }
+                let missing = cost[material] - materials[material];
+                wait = wait.max(missing.div_ceil(robots[material]));
+            }
+        }
+        Some(wait + 1)
+    }
+}
+
+// Returns the most geodes for every blueprint, in the same order. The blueprints are independent,
+// so they are evaluated in parallel.
+fn max_geodes(blueprints: &[Blueprint], time: u32) -> Vec<u32> {
+    thread::scope(|scope| {
+        let handles = blueprints
+            .iter()
+            .map(|blueprint| scope.spawn(move || blueprint.max_geodes(time)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[aoc_generator(day19)]
+fn parse_input(input: &str) -> Result<Vec<Blueprint>, ParseError> {
+    let input = normalise(input);
+    let re = Regex::new(concat!(
+        r"^Blueprint (\d+): ",
+        r"Each ore robot costs (\d+) ore\. ",
+        r"Each clay robot costs (\d+) ore\. ",
+        r"Each obsidian robot costs (\d+) ore and (\d+) clay\. ",
+        r"Each geode robot costs (\d+) ore and (\d+) obsidian\.$",
+    ))
+    .unwrap();
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| ParseError::new(19, i + 1, 1, l, "invalid blueprint"))?;
+            // The numbers are matched by the regex, so they can only fail to parse if they are
+            // out of range.
+            let number = |n: usize| {
+                let m = caps.get(n).unwrap();
+                m.as_str()
+                    .parse()
+                    .map_err(|_| ParseError::new(19, i + 1, m.start() + 1, l, "invalid number"))
+            };
+            Ok(Blueprint {
+                id: number(1)?,
+                costs: [
+                    [number(2)?, 0, 0],
+                    [number(3)?, 0, 0],
+                    [number(4)?, number(5)?, 0],
+                    [number(6)?, 0, number(7)?],
+                ],
+            })
+        })
+        .collect()
+}
+
+#[aoc(day19, part1)]
+fn part1(blueprints: &[Blueprint]) -> u32 {
+    blueprints
+        .iter()
+        .zip(max_geodes(blueprints, 24))
+        .map(|(blueprint, geodes)| blueprint.id * geodes)
+        .sum()
+}
+
+#[aoc(day19, part2)]
+fn part2(blueprints: &[Blueprint]) -> u32 {
+    let blueprints = &blueprints[..blueprints.len().min(3)];
+    max_geodes(blueprints, 32).into_iter().product()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(19, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let expected = vec![
+            Blueprint {
+                id: 1,
+                costs: [[4, 0, 0], [2, 0, 0], [3, 14, 0], [2, 0, 7]],
+            },
+            Blueprint {
+                id: 2,
+                costs: [[2, 0, 0], [3, 0, 0], [3, 8, 0], [3, 0, 12]],
+            },
+        ];
+
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("costs 3 ore and 8 clay", "costs 3 ore, 8 clay");
+        let line = input.lines().nth(1).unwrap();
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(19, 2, 1, line, "invalid blueprint"), error);
+
+        let input = get_test_input().replace("Blueprint 2:", "Blueprint 99999999999:");
+        let line = input.lines().nth(1).unwrap();
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(19, 2, 11, line, "invalid number"), error);
+    }
+
+    #[test]
+    fn test_max_geodes() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(vec![9, 12], max_geodes(&input, 24));
+        // Without obsidian robots, no geodes can be opened at all.
+        let blueprint = Blueprint {
+            id: 1,
+            costs: [[4, 0, 0], [2, 0, 0], [3, 100, 0], [2, 0, 7]],
+        };
+        assert_eq!(0, blueprint.max_geodes(24));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(33, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(56 * 62, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+            Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.
+        "}
+    }
+}
@@ -1,3 +1,6 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -16,11 +19,12 @@ impl Unit {
         }
     }
 
-    fn from_char(c: char) -> Unit {
+    fn from_char(c: char) -> Option<Unit> {
         match c {
-            'A' | 'X' => Unit::Rock,
-            'B' | 'Y' => Unit::Paper,
-            _ => Unit::Scissors,
+            'A' | 'X' => Some(Unit::Rock),
+            'B' | 'Y' => Some(Unit::Paper),
+            'C' | 'Z' => Some(Unit::Scissors),
+            _ => None,
         }
     }
 
@@ -43,11 +47,12 @@ enum Outcome {
 }
 
 impl Outcome {
-    fn from_char(c: char) -> Outcome {
+    fn from_char(c: char) -> Option<Outcome> {
         match c {
-            'Z' => Outcome::Win,
-            'Y' => Outcome::Draw,
-            _ => Outcome::Loss,
+            'Z' => Some(Outcome::Win),
+            'Y' => Some(Outcome::Draw),
+            'X' => Some(Outcome::Loss),
+            _ => None,
         }
     }
 
@@ -68,31 +73,40 @@ impl Outcome {
     }
 }
 
+// Parses a line in the format "A X", converting both columns with the given functions.
+fn parse_line<A, B>(
+    i: usize,
+    line: &str,
+    parse_a: fn(char) -> Option<A>,
+    parse_b: fn(char) -> Option<B>,
+) -> Result<(A, B), ParseError> {
+    let error = |column, reason| ParseError::new(2, i + 1, column, line, reason);
+    let chars = line.chars().collect::<Vec<char>>();
+    if chars.len() != 3 || chars[1] != ' ' {
+        return Err(error(1, "expected two columns separated by a space"));
+    }
+    let a = parse_a(chars[0]).ok_or_else(|| error(1, "invalid opponent move"))?;
+    let b = parse_b(chars[2]).ok_or_else(|| error(3, "invalid strategy"))?;
+    Ok((a, b))
+}
+
 #[aoc_generator(day2, part1)]
-fn parse_input_part1(input: &str) -> Vec<(Unit, Unit)> {
+fn parse_input_part1(input: &str) -> Result<Vec<(Unit, Unit)>, ParseError> {
+    let input = normalise(input);
     input
         .lines()
-        .map(|line| {
-            let mut chars = line.chars();
-            let a = Unit::from_char(chars.next().unwrap());
-            chars.next().unwrap();
-            let b = Unit::from_char(chars.next().unwrap());
-            (a, b)
-        })
+        .enumerate()
+        .map(|(i, line)| parse_line(i, line, Unit::from_char, Unit::from_char))
         .collect()
 }
 
 #[aoc_generator(day2, part2)]
-fn parse_input_part2(input: &str) -> Vec<(Unit, Outcome)> {
+fn parse_input_part2(input: &str) -> Result<Vec<(Unit, Outcome)>, ParseError> {
+    let input = normalise(input);
     input
         .lines()
-        .map(|line| {
-            let mut chars = line.chars();
-            let a = Unit::from_char(chars.next().unwrap());
-            chars.next().unwrap();
-            let b = Outcome::from_char(chars.next().unwrap());
-            (a, b)
-        })
+        .enumerate()
+        .map(|(i, line)| parse_line(i, line, Unit::from_char, Outcome::from_char))
         .collect()
 }
 
@@ -117,9 +131,14 @@ fn part2(games: &[(Unit, Outcome)]) -> usize {
     score
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(2, parse_input_part1, part1, parse_input_part2, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -130,7 +149,7 @@ mod tests {
             (Unit::Scissors, Unit::Scissors),
         ];
 
-        assert_eq!(expected, parse_input_part1(get_test_input()));
+        assert_eq!(Ok(expected), parse_input_part1(get_test_input()));
     }
 
     #[test]
@@ -141,18 +160,40 @@ mod tests {
             (Unit::Scissors, Outcome::Win),
         ];
 
-        assert_eq!(expected, parse_input_part2(get_test_input()));
+        assert_eq!(Ok(expected), parse_input_part2(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected_part1 = parse_input_part1(get_test_input());
+        let expected_part2 = parse_input_part2(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected_part1, parse_input_part1(&input));
+            assert_eq!(expected_part2, parse_input_part2(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input_part1("A Y\nB W\n").unwrap_err();
+        assert_eq!(ParseError::new(2, 2, 3, "B W", "invalid strategy"), error);
+
+        let error = parse_input_part2("AY\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(2, 1, 1, "AY", "expected two columns separated by a space"),
+            error
+        );
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input_part1(get_test_input());
+        let input = parse_input_part1(get_test_input()).unwrap();
         assert_eq!(15, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input_part2(get_test_input());
+        let input = parse_input_part2(get_test_input()).unwrap();
         assert_eq!(12, part2(&input));
     }
 
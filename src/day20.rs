@@ -0,0 +1,261 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+// A list of the indexes of the numbers in the original file, split into blocks of roughly equal
+// size. Finding, removing and inserting an index only touches a single block and the list of
+// blocks, which takes O(sqrt(n)) instead of the O(n) of a single `Vec`.
+struct BlockList {
+    blocks: Vec<Vec<usize>>,
+    // The block that contains each index.
+    block_of: Vec<usize>,
+    block_size: usize,
+    // The number of moves since the blocks were last rebalanced.
+    moves: usize,
+}
+
+impl BlockList {
+    fn new(len: usize) -> Self {
+        let block_size = (len as f64).sqrt().ceil().max(1.0) as usize;
+        let mut list = Self {
+            blocks: vec![(0..len).collect()],
+            block_of: vec![0; len],
+            block_size,
+            moves: 0,
+        };
+        list.rebalance();
+        list
+    }
+
+    fn len(&self) -> usize {
+        self.block_of.len()
+    }
+
+    // Splits the list into blocks of equal size again, as the sizes drift apart when indexes
+    // move between blocks.
+    fn rebalance(&mut self) {
+        let indexes = self.blocks.concat();
+        self.blocks = indexes
+            .chunks(self.block_size)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &index in block {
+                self.block_of[index] = b;
+            }
+        }
+        self.moves = 0;
+    }
+
+    // Removes the given index from the list and returns the position it was at.
+    fn remove(&mut self, index: usize) -> usize {
+        let b = self.block_of[index];
+        let offset = self.blocks[b].iter().position(|&i| i == index).unwrap();
+        self.blocks[b].remove(offset);
+        self.blocks[..b].iter().map(Vec::len).sum::<usize>() + offset
+    }
+
+    // Inserts the index at the given position, which may be at the end of the list.
+    fn insert(&mut self, position: usize, index: usize) {
+        let mut offset = position;
+        let mut b = 0;
+        while b < self.blocks.len() - 1 && offset > self.blocks[b].len() {
+            offset -= self.blocks[b].len();
+            b += 1;
+        }
+        self.blocks[b].insert(offset, index);
+        self.block_of[index] = b;
+    }
+
+    // Moves the index forward by the given number of positions, wrapping around the list.
+    fn shift(&mut self, index: usize, distance: i64) {
+        let position = self.remove(index);
+        // While the index is removed, the list is one element shorter.
+        let len = self.len() as i64 - 1;
+        let position = (position as i64 + distance).rem_euclid(len) as usize;
+        self.insert(position, index);
+
+        self.moves += 1;
+        if self.moves >= self.block_size {
+            self.rebalance();
+        }
+    }
+
+    fn indexes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
+    }
+}
+
+// Mixes the numbers the given number of times and returns them in their new order. The numbers
+// are moved in their original order, so duplicates are told apart by their original index.
+fn mix(numbers: &[i64], rounds: usize) -> Vec<i64> {
+    if numbers.len() < 2 {
+        return numbers.to_vec();
+    }
+    let mut list = BlockList::new(numbers.len());
+    for _ in 0..rounds {
+        for (index, &number) in numbers.iter().enumerate() {
+            list.shift(index, number);
+        }
+    }
+    list.indexes().map(|index| numbers[index]).collect()
+}
+
+// Returns the sum of the numbers 1000, 2000 and 3000 positions after the zero.
+fn grove_coordinates(mixed: &[i64]) -> i64 {
+    let zero = mixed.iter().position(|&n| n == 0).unwrap();
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()])
+        .sum()
+}
+
+#[aoc_generator(day20)]
+fn parse_input(input: &str) -> Result<Vec<i64>, ParseError> {
+    let input = normalise(input);
+    let numbers = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| ParseError::new(20, i + 1, 1, line, "invalid number"))
+        })
+        .collect::<Result<Vec<i64>, ParseError>>()?;
+    // The grove coordinates are found relative to the zero.
+    if !numbers.contains(&0) {
+        let line = input.lines().count() + 1;
+        return Err(ParseError::new(20, line, 1, "", "missing zero"));
+    }
+    Ok(numbers)
+}
+
+#[aoc(day20, part1)]
+fn part1(numbers: &[i64]) -> i64 {
+    grove_coordinates(&mix(numbers, 1))
+}
+
+#[aoc(day20, part2)]
+fn part2(numbers: &[i64]) -> i64 {
+    let numbers = numbers
+        .iter()
+        .map(|n| n * DECRYPTION_KEY)
+        .collect::<Vec<i64>>();
+    grove_coordinates(&mix(&numbers, 10))
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(20, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    // Mixes the numbers by moving them around in a single `Vec`, exactly as described in the
+    // puzzle.
+    fn mix_reference(numbers: &[i64], rounds: usize) -> Vec<i64> {
+        let mut indexes = (0..numbers.len()).collect::<Vec<usize>>();
+        let len = numbers.len() as i64 - 1;
+        for _ in 0..rounds {
+            for (index, &number) in numbers.iter().enumerate() {
+                let position = indexes.iter().position(|&i| i == index).unwrap();
+                indexes.remove(position);
+                let position = (position as i64 + number).rem_euclid(len) as usize;
+                indexes.insert(position, index);
+            }
+        }
+        indexes.iter().map(|&index| numbers[index]).collect()
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let expected = vec![1, 2, -3, 3, -2, 0, 4];
+
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("-3", "- 3");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(20, 3, 1, "- 3", "invalid number"), error);
+
+        let input = get_test_input().replace('0', "5");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(20, 8, 1, "", "missing zero"), error);
+    }
+
+    #[test]
+    fn test_mix() {
+        let input = parse_input(get_test_input()).unwrap();
+        let mut mixed = mix(&input, 1);
+        assert_eq!(mix_reference(&input, 1), mixed);
+        // The list is circular, so only the order relative to the zero matters.
+        let zero = mixed.iter().position(|&n| n == 0).unwrap();
+        mixed.rotate_left(zero);
+        assert_eq!(vec![0, 3, -2, 1, 2, -3, 4], mixed);
+    }
+
+    #[test]
+    fn test_mix_against_reference() {
+        // Pseudo-random lists with duplicates and numbers larger than the list.
+        let mut seed = 12345u64;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as i64
+        };
+        for len in [2, 3, 5, 17, 100, 257] {
+            let numbers = (0..len)
+                .map(|_| random() % 1000 - 500)
+                .collect::<Vec<i64>>();
+            for rounds in [1, 3] {
+                assert_eq!(
+                    mix_reference(&numbers, rounds),
+                    mix(&numbers, rounds),
+                    "{} numbers, {} rounds",
+                    len,
+                    rounds
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(3, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(1623178306, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            1
+            2
+            -3
+            3
+            -2
+            0
+            4
+        "}
+    }
+}
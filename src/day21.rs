@@ -0,0 +1,493 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::{HashMap, HashSet};
+use std::ops::{Add, Mul, Sub};
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Job {
+    Number(i64),
+    Operation(String, Operator, String),
+}
+
+type Monkeys = HashMap<String, Job>;
+
+// An exact fraction, so the divisions never lose precision. The denominator is always positive
+// and shares no factors with the numerator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    fn new(numerator: i128, denominator: i128) -> Self {
+        let gcd = gcd(numerator, denominator).max(1) * denominator.signum();
+        Self {
+            numerator: numerator / gcd,
+            denominator: denominator / gcd,
+        }
+    }
+
+    fn checked_div(self, other: Self) -> Option<Self> {
+        (other.numerator != 0).then(|| {
+            Self::new(
+                self.numerator * other.denominator,
+                self.denominator * other.numerator,
+            )
+        })
+    }
+
+    fn to_integer(self) -> Option<i64> {
+        (self.denominator == 1)
+            .then(|| i64::try_from(self.numerator).ok())
+            .flatten()
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::new(value as i128, 1)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.numerator * other.denominator + other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.numerator * other.denominator - other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.numerator * other.numerator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// Returns the monkeys in an order where every monkey comes after the monkeys it listens to, or
+// the name of a monkey whose job depends on its own number.
+//
+// The jobs are visited with an explicit stack rather than recursively, so long chains of jobs
+// cannot overflow the call stack. Everything that walks the jobs follows this order.
+fn dependency_order(monkeys: &Monkeys) -> Result<Vec<&str>, &str> {
+    // Monkeys that are still being visited are mapped to false, and finished ones to true.
+    let mut visited: HashMap<&str, bool> = HashMap::new();
+    let mut order = Vec::with_capacity(monkeys.len());
+    for start in monkeys.keys() {
+        if visited.contains_key(start.as_str()) {
+            continue;
+        }
+        // Monkeys, and whether the monkeys they listen to have been pushed.
+        let mut stack = vec![(start.as_str(), false)];
+        while let Some((name, expanded)) = stack.pop() {
+            if expanded {
+                visited.insert(name, true);
+                order.push(name);
+                continue;
+            }
+            match visited.get(name) {
+                Some(true) => continue,
+                Some(false) => return Err(name),
+                None => {}
+            }
+            visited.insert(name, false);
+            stack.push((name, true));
+            if let Job::Operation(left, _, right) = &monkeys[name] {
+                for other in [left, right] {
+                    match visited.get(other.as_str()) {
+                        Some(false) => return Err(other),
+                        Some(true) => {}
+                        None => stack.push((other, false)),
+                    }
+                }
+            }
+        }
+    }
+    Ok(order)
+}
+
+// Returns the number every monkey yells, or `None` for the monkeys whose job divides by zero.
+fn evaluate(monkeys: &Monkeys) -> HashMap<&str, Option<Rational>> {
+    let order = dependency_order(monkeys).expect("jobs are acyclic");
+    let mut numbers: HashMap<&str, Option<Rational>> = HashMap::with_capacity(order.len());
+    for name in order {
+        let number = match &monkeys[name] {
+            Job::Number(value) => Some(Rational::from(*value)),
+            Job::Operation(left, operator, right) => {
+                match (numbers[left.as_str()], numbers[right.as_str()]) {
+                    (Some(left), Some(right)) => match operator {
+                        Operator::Add => Some(left + right),
+                        Operator::Subtract => Some(left - right),
+                        Operator::Multiply => Some(left * right),
+                        Operator::Divide => left.checked_div(right),
+                    },
+                    _ => None,
+                }
+            }
+        };
+        numbers.insert(name, number);
+    }
+    numbers
+}
+
+// Returns the monkeys whose number depends on the human.
+fn depends_on_human(monkeys: &Monkeys) -> HashSet<&str> {
+    let order = dependency_order(monkeys).expect("jobs are acyclic");
+    let mut dependent = HashSet::new();
+    for name in order {
+        let depends = name == HUMAN
+            || match &monkeys[name] {
+                Job::Number(_) => false,
+                Job::Operation(left, _, right) => {
+                    dependent.contains(left.as_str()) || dependent.contains(right.as_str())
+                }
+            };
+        if depends {
+            dependent.insert(name);
+        }
+    }
+    dependent
+}
+
+// Finds the number the human has to yell so that both monkeys the root listens to yell the same
+// number.
+//
+// Exactly one side of every operation on the path from the root to the human depends on the
+// human. The other side is a known number, so each operation can be inverted in turn to find the
+// number the side that depends on the human has to yell.
+fn solve_for_human(monkeys: &Monkeys) -> Option<Rational> {
+    let Job::Operation(left, _, right) = &monkeys[ROOT] else {
+        return None;
+    };
+    let numbers = evaluate(monkeys);
+    let dependent = depends_on_human(monkeys);
+    let sides = |left: &str, right: &str| (dependent.contains(left), dependent.contains(right));
+
+    let (mut name, mut target) = match sides(left, right) {
+        (true, false) => (left, numbers[right.as_str()]?),
+        (false, true) => (right, numbers[left.as_str()]?),
+        _ => return None,
+    };
+
+    while name != HUMAN {
+        let Job::Operation(left, operator, right) = &monkeys[name] else {
+            return None;
+        };
+        target = match sides(left, right) {
+            // target = human op known
+            (true, false) => {
+                let known = numbers[right.as_str()]?;
+                name = left;
+                match operator {
+                    Operator::Add => target - known,
+                    Operator::Subtract => target + known,
+                    Operator::Multiply => target.checked_div(known)?,
+                    Operator::Divide => target * known,
+                }
+            }
+            // target = known op human
+            (false, true) => {
+                let known = numbers[left.as_str()]?;
+                name = right;
+                match operator {
+                    Operator::Add => target - known,
+                    Operator::Subtract => known - target,
+                    Operator::Multiply => target.checked_div(known)?,
+                    Operator::Divide => known.checked_div(target)?,
+                }
+            }
+            _ => return None,
+        };
+    }
+
+    Some(target)
+}
+
+#[aoc_generator(day21)]
+fn parse_input(input: &str) -> Result<Monkeys, ParseError> {
+    let input = normalise(input);
+    let mut monkeys = Monkeys::new();
+    for (i, line) in input.lines().enumerate() {
+        let error = |column, reason| ParseError::new(21, i + 1, column, line, reason);
+        let (name, job) = line
+            .split_once(": ")
+            .ok_or_else(|| error(1, "expected a name and a job"))?;
+        let job = match job.split(' ').collect::<Vec<&str>>()[..] {
+            [number] => Job::Number(
+                number
+                    .parse()
+                    .map_err(|_| error(name.len() + 3, "invalid number"))?,
+            ),
+            [left, operator, right] => {
+                let operator = match operator {
+                    "+" => Operator::Add,
+                    "-" => Operator::Subtract,
+                    "*" => Operator::Multiply,
+                    "/" => Operator::Divide,
+                    _ => return Err(error(name.len() + left.len() + 4, "invalid operator")),
+                };
+                Job::Operation(left.to_string(), operator, right.to_string())
+            }
+            _ => return Err(error(name.len() + 3, "invalid job")),
+        };
+        if monkeys.insert(name.to_string(), job).is_some() {
+            return Err(error(1, "duplicate monkey"));
+        }
+    }
+
+    // Every monkey must listen to monkeys that exist.
+    for (i, line) in input.lines().enumerate() {
+        let name = line.split_once(": ").unwrap().0;
+        if let Job::Operation(left, _, right) = &monkeys[name] {
+            for (other, column) in [
+                (left, name.len() + 3),
+                (right, line.len() - right.len() + 1),
+            ] {
+                if !monkeys.contains_key(other) {
+                    return Err(ParseError::new(21, i + 1, column, line, "unknown monkey"));
+                }
+            }
+        }
+    }
+    for name in [ROOT, HUMAN] {
+        if !monkeys.contains_key(name) {
+            let line = input.lines().count() + 1;
+            let reason = format!("missing monkey {}", name);
+            return Err(ParseError::new(21, line, 1, "", &reason));
+        }
+    }
+    // The jobs are evaluated in order of dependency, so a monkey must never end up listening to
+    // itself.
+    if let Err(name) = dependency_order(&monkeys) {
+        let (i, line) = input
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.split_once(": ").unwrap().0 == name)
+            .unwrap();
+        return Err(ParseError::new(21, i + 1, 1, line, "cyclic job"));
+    }
+
+    Ok(monkeys)
+}
+
+#[aoc(day21, part1)]
+fn part1(monkeys: &Monkeys) -> Option<i64> {
+    evaluate(monkeys)[ROOT]?.to_integer()
+}
+
+#[aoc(day21, part2)]
+fn part2(monkeys: &Monkeys) -> Option<i64> {
+    solve_for_human(monkeys)?.to_integer()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(21, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(15, input.len());
+        assert_eq!(
+            Job::Operation("pppw".to_string(), Operator::Add, "sjmn".to_string()),
+            input["root"]
+        );
+        assert_eq!(Job::Number(5), input["humn"]);
+        assert_eq!(
+            Job::Operation("cczh".to_string(), Operator::Divide, "lfqf".to_string()),
+            input["pppw"]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("dbpl: 5", "dbpl: x");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(21, 2, 7, "dbpl: x", "invalid number"),
+            error
+        );
+
+        let input = get_test_input().replace("pppw + sjmn", "pppw % sjmn");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(21, 1, 12, "root: pppw % sjmn", "invalid operator"),
+            error
+        );
+
+        let input = get_test_input().replace("pppw + sjmn", "pppw + abcd");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(21, 1, 14, "root: pppw + abcd", "unknown monkey"),
+            error
+        );
+
+        let input = get_test_input().replace("humn: 5\n", "");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(21, 5, 7, "ptdq: humn - dvpt", "unknown monkey"),
+            error
+        );
+
+        let input = get_test_input().replace("root: pppw + sjmn", "rot: pppw + sjmn");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(21, 16, 1, "", "missing monkey root"), error);
+
+        let input = get_test_input().replace("zczc: 2", "dbpl: 2");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(21, 4, 1, "dbpl: 2", "duplicate monkey"),
+            error
+        );
+
+        let input = get_test_input().replace("hmdt: 32", "hmdt: sjmn + zczc");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!("cyclic job", error.reason);
+    }
+
+    #[test]
+    fn test_rational() {
+        let a = Rational::new(6, -4);
+        assert_eq!(Rational::new(-3, 2), a);
+        assert_eq!((-3, 2), (a.numerator, a.denominator));
+        assert_eq!(Rational::from(-1), a + Rational::new(1, 2));
+        assert_eq!(Rational::new(-7, 4), a - Rational::new(1, 4));
+        assert_eq!(Rational::from(-3), a * Rational::from(2));
+        assert_eq!(Some(Rational::new(-1, 2)), a.checked_div(Rational::from(3)));
+        assert_eq!(None, a.checked_div(Rational::from(0)));
+        assert_eq!(None, a.to_integer());
+        assert_eq!(Some(-3), (a * Rational::from(2)).to_integer());
+    }
+
+    #[test]
+    fn test_solve_with_fractions() {
+        // The human has to yell a number that is only an integer after dividing by 4.
+        let input = indoc! {"
+            root: left + right
+            left: humn / four
+            four: 4
+            right: 3
+            humn: 0
+        "};
+        let monkeys = parse_input(input).unwrap();
+        assert_eq!(Some(12), part2(&monkeys));
+
+        // The human is the divisor, and the number in between is a fraction.
+        let input = indoc! {"
+            root: left + right
+            left: three / half
+            half: humn * two
+            three: 3
+            two: 2
+            right: 2
+            humn: 0
+        "};
+        let monkeys = parse_input(input).unwrap();
+        assert_eq!(Some(Rational::new(3, 4)), solve_for_human(&monkeys));
+        assert_eq!(None, part2(&monkeys));
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // Far too deep to evaluate recursively: every monkey adds one to the next.
+        const LENGTH: usize = 100_000;
+        let mut input = String::from("root: m0 + target\ntarget: 200000\none: 1\nhumn: 5\n");
+        for i in 0..LENGTH {
+            let next = if i + 1 == LENGTH {
+                HUMAN.to_string()
+            } else {
+                format!("m{}", i + 1)
+            };
+            input.push_str(&format!("m{}: {} + one\n", i, next));
+        }
+        let monkeys = parse_input(&input).unwrap();
+        assert_eq!(Some(300005), part1(&monkeys));
+        assert_eq!(Some(100000), part2(&monkeys));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(152), part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(301), part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            root: pppw + sjmn
+            dbpl: 5
+            cczh: sllz + lgvd
+            zczc: 2
+            ptdq: humn - dvpt
+            dvpt: 3
+            lfqf: 4
+            humn: 5
+            ljgn: 2
+            sjmn: drzm * dbpl
+            sllz: 4
+            pppw: cczh / lfqf
+            lgvd: ljgn * ptdq
+            drzm: hmdt - zczc
+            hmdt: 32
+        "}
+    }
+}
@@ -0,0 +1,486 @@
+use crate::error::ParseError;
+use crate::geometry::{Direction, Point, Point3};
+use crate::grid::Grid;
+use crate::input::{normalise, paragraphs};
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Notes {
+    map: Grid<Tile>,
+    path: Vec<Step>,
+}
+
+impl Notes {
+    fn tile(&self, position: Point<isize>) -> Tile {
+        match position.to_unsigned() {
+            Some(p) => self.map.get(p.x, p.y).copied().unwrap_or(Tile::Void),
+            None => Tile::Void,
+        }
+    }
+
+    // Follows the path and returns the final password. When a step leaves the map, the `wrap`
+    // function returns the position and facing on the other side.
+    fn follow<F>(&self, wrap: F) -> usize
+    where
+        F: Fn(Point<isize>, Direction) -> (Point<isize>, Direction),
+    {
+        // The parser made sure there is an open tile in the top row.
+        let (x, _) = self.map.position(|&t| t == Tile::Open).unwrap();
+        let mut position = Point::new(x as isize, 0);
+        let mut direction = Direction::East;
+
+        for step in &self.path {
+            match step {
+                Step::Left => direction = direction.turn_left(),
+                Step::Right => direction = direction.turn_right(),
+                Step::Forward(distance) => {
+                    for _ in 0..*distance {
+                        let mut next = (position + direction.delta(), direction);
+                        if self.tile(next.0) == Tile::Void {
+                            next = wrap(position, direction);
+                        }
+                        if self.tile(next.0) == Tile::Wall {
+                            break;
+                        }
+                        (position, direction) = next;
+                    }
+                }
+            }
+        }
+
+        let facing = match direction {
+            Direction::East => 0,
+            Direction::South => 1,
+            Direction::West => 2,
+            Direction::North => 3,
+        };
+        1000 * (position.y as usize + 1) + 4 * (position.x as usize + 1) + facing
+    }
+
+    // Wraps around to the other side of the map, as if it were flat.
+    fn wrap_flat(&self, position: Point<isize>, direction: Direction) -> (Point<isize>, Direction) {
+        let back = direction.reverse().delta();
+        let mut position = position;
+        while self.tile(position + back) != Tile::Void {
+            position += back;
+        }
+        (position, direction)
+    }
+}
+
+// A square of the map that has been folded onto the cube. The vectors describe its orientation in
+// three dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Face {
+    // The top left corner of the face on the map.
+    corner: Point<isize>,
+    // The directions east and south on the face.
+    right: Point3<isize>,
+    down: Point3<isize>,
+    // The direction pointing out of the cube.
+    normal: Point3<isize>,
+}
+
+impl Face {
+    fn vector(&self, direction: Direction) -> Point3<isize> {
+        match direction {
+            Direction::North => -self.down,
+            Direction::East => self.right,
+            Direction::South => self.down,
+            Direction::West => -self.right,
+        }
+    }
+
+    // Returns the face that is attached to the given side of this one when the map is folded.
+    // The new face is tilted by a quarter turn around the shared edge.
+    fn fold(&self, direction: Direction, corner: Point<isize>) -> Self {
+        let Face {
+            right,
+            down,
+            normal,
+            ..
+        } = *self;
+        let (right, down, normal) = match direction {
+            Direction::North => (right, normal, -down),
+            Direction::East => (-normal, down, right),
+            Direction::South => (right, -normal, down),
+            Direction::West => (normal, down, -right),
+        };
+        Self {
+            corner,
+            right,
+            down,
+            normal,
+        }
+    }
+}
+
+// The map folded into a cube.
+//
+// Every square of the map is placed on a cube whose faces have a side of `2 * size`, centred on
+// the origin, so the centre of every square has integer coordinates. Walking off a face moves to
+// the face whose normal points in the direction of travel.
+#[derive(Debug, Clone)]
+struct Cube {
+    size: isize,
+    faces: Vec<Face>,
+    // The face that covers each square of the map, by its position in units of faces.
+    layout: HashMap<Point<isize>, usize>,
+}
+
+impl Cube {
+    // Folds the map into a cube, or returns `None` if the map is not the net of a cube.
+    fn fold(map: &Grid<Tile>) -> Option<Self> {
+        let area = map
+            .positions()
+            .filter(|&(x, y)| map[(x, y)] != Tile::Void)
+            .count();
+        let size = (area / 6).isqrt();
+        if size == 0 || 6 * size * size != area {
+            return None;
+        }
+
+        // Find the squares of the map that are completely covered by tiles. Since the area is
+        // exactly six faces, any tiles outside of them mean there are fewer than six.
+        let mut squares = vec![];
+        for fy in 0..map.height() / size {
+            for fx in 0..map.width() / size {
+                let covered = (0..size).all(|dy| {
+                    (0..size).all(|dx| map[(fx * size + dx, fy * size + dy)] != Tile::Void)
+                });
+                if covered {
+                    squares.push(Point::new(fx as isize, fy as isize));
+                }
+            }
+        }
+        if squares.len() != 6 {
+            return None;
+        }
+
+        // Fold the faces one by one, starting from the first one, which lies flat on top.
+        let size = size as isize;
+        let mut faces = vec![Face {
+            corner: squares[0] * size,
+            right: Point3::new(1, 0, 0),
+            down: Point3::new(0, 1, 0),
+            normal: Point3::new(0, 0, -1),
+        }];
+        let mut layout = HashMap::from([(squares[0], 0)]);
+        let mut queue = vec![squares[0]];
+        while let Some(square) = queue.pop() {
+            let face = faces[layout[&square]];
+            for direction in Direction::VALUES {
+                let next = square + direction.delta();
+                if squares.contains(&next) && !layout.contains_key(&next) {
+                    layout.insert(next, faces.len());
+                    faces.push(face.fold(direction, next * size));
+                    queue.push(next);
+                }
+            }
+        }
+        // The squares must be connected and fold onto six different faces.
+        let all_sides = Point3::DIRECTIONS
+            .iter()
+            .all(|side| faces.iter().any(|face| face.normal == *side));
+        if faces.len() != 6 || !all_sides {
+            return None;
+        }
+
+        Some(Self {
+            size,
+            faces,
+            layout,
+        })
+    }
+
+    // Walks over the edge of the cube onto the next face.
+    fn wrap(&self, position: Point<isize>, direction: Direction) -> (Point<isize>, Direction) {
+        let size = self.size;
+        let square = Point::new(position.x / size, position.y / size);
+        let from = self.faces[self.layout[&square]];
+        let local = position - from.corner;
+        let centre = from.normal * size
+            + from.right * (2 * local.x + 1 - size)
+            + from.down * (2 * local.y + 1 - size);
+
+        // The next square lies on the face in the direction of travel, right next to the edge.
+        let travel = from.vector(direction);
+        let to = self
+            .faces
+            .iter()
+            .find(|face| face.normal == travel)
+            .unwrap();
+        let centre = centre + travel - from.normal;
+        let local = Point::new(
+            (centre.dot(&to.right) + size - 1) / 2,
+            (centre.dot(&to.down) + size - 1) / 2,
+        );
+        // Walking down the side of the cube means heading into it from the previous face.
+        let direction = Direction::VALUES
+            .into_iter()
+            .find(|&d| to.vector(d) == -from.normal)
+            .unwrap();
+        (to.corner + local, direction)
+    }
+}
+
+#[aoc_generator(day22)]
+fn parse_input(input: &str) -> Result<Notes, ParseError> {
+    let input = normalise(input);
+    let paragraphs = paragraphs(&input);
+    if let Some((line, text)) = paragraphs.get(2) {
+        let text = text.lines().next().unwrap();
+        return Err(ParseError::new(
+            22,
+            line + 1,
+            1,
+            text,
+            "unexpected empty line",
+        ));
+    }
+    let [(_, map_input), (path_line, path_input)] = paragraphs[..] else {
+        let line = input.lines().count() + 1;
+        return Err(ParseError::new(22, line, 1, "", "missing path"));
+    };
+
+    // The lines of the map have different lengths, so it is padded with void.
+    let width = map_input.lines().map(str::len).max().unwrap();
+    let height = map_input.lines().count();
+    let mut map = Grid::new(width, height, Tile::Void);
+    for (y, line) in map_input.lines().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            map[(x, y)] = match c {
+                ' ' => Tile::Void,
+                '.' => Tile::Open,
+                '#' => Tile::Wall,
+                _ => return Err(ParseError::new(22, y + 1, x + 1, line, "invalid tile")),
+            };
+        }
+    }
+    if !map
+        .row(0)
+        .is_some_and(|mut row| row.any(|&t| t == Tile::Open))
+    {
+        return Err(ParseError::new(
+            22,
+            1,
+            1,
+            map_input.lines().next().unwrap(),
+            "no open tile in the top row",
+        ));
+    }
+
+    if let Some((i, extra)) = path_input.lines().enumerate().nth(1) {
+        let line = path_line + i + 1;
+        return Err(ParseError::new(22, line, 1, extra, "unexpected line"));
+    }
+    let mut path = vec![];
+    let mut distance: Option<usize> = None;
+    for (i, c) in path_input.chars().enumerate() {
+        let error = |reason| ParseError::new(22, path_line + 1, i + 1, path_input, reason);
+        match c {
+            '0'..='9' => {
+                let digit = c.to_digit(10).unwrap() as usize;
+                let value = distance
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|d| d.checked_add(digit))
+                    .ok_or_else(|| error("invalid distance"))?;
+                distance = Some(value);
+            }
+            'L' | 'R' => {
+                if let Some(distance) = distance.take() {
+                    path.push(Step::Forward(distance));
+                }
+                path.push(if c == 'L' { Step::Left } else { Step::Right });
+            }
+            _ => return Err(error("invalid step")),
+        }
+    }
+    if let Some(distance) = distance {
+        path.push(Step::Forward(distance));
+    }
+
+    Ok(Notes { map, path })
+}
+
+#[aoc(day22, part1)]
+fn part1(notes: &Notes) -> usize {
+    notes.follow(|position, direction| notes.wrap_flat(position, direction))
+}
+
+#[aoc(day22, part2)]
+fn part2(notes: &Notes) -> Option<usize> {
+    let cube = Cube::fold(&notes.map)?;
+    Some(notes.follow(|position, direction| cube.wrap(position, direction)))
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(22, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!((16, 12), (input.map.width(), input.map.height()));
+        assert_eq!(Tile::Void, input.map[(0, 0)]);
+        assert_eq!(Tile::Open, input.map[(8, 0)]);
+        assert_eq!(Tile::Wall, input.map[(11, 0)]);
+        assert_eq!(Tile::Void, input.map[(15, 0)]);
+        assert_eq!(
+            vec![
+                Step::Forward(10),
+                Step::Right,
+                Step::Forward(5),
+                Step::Left,
+                Step::Forward(5)
+            ],
+            input.path[..5]
+        );
+        assert_eq!(13, input.path.len());
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("...#.......#", "...#...x...#");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(22, 5, 8, "...#...x...#", "invalid tile"),
+            error
+        );
+
+        let input = get_test_input().replace("5L5R", "5L5X");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(22, 14, 7, "10R5L5X10L4R5L5", "invalid step"),
+            error
+        );
+
+        let input = get_test_input().replace("\n\n", "\n");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(22, 14, 1, "", "missing path"), error);
+    }
+
+    #[test]
+    fn test_fold() {
+        let input = parse_input(get_test_input()).unwrap();
+        let cube = Cube::fold(&input.map).unwrap();
+        assert_eq!(4, cube.size);
+
+        // The example from the puzzle description: walking east off face 4 onto face 6, and
+        // south off face 5 onto face 2.
+        assert_eq!(
+            (Point::new(14, 8), Direction::South),
+            cube.wrap(Point::new(11, 5), Direction::East)
+        );
+        assert_eq!(
+            (Point::new(1, 7), Direction::North),
+            cube.wrap(Point::new(10, 11), Direction::South)
+        );
+
+        // Walking off any edge and back again ends up where it started.
+        for (x, y) in input.map.positions() {
+            let position = Point::new(x as isize, y as isize);
+            if input.tile(position) == Tile::Void {
+                continue;
+            }
+            for direction in Direction::VALUES {
+                if input.tile(position + direction.delta()) != Tile::Void {
+                    continue;
+                }
+                let (next, facing) = cube.wrap(position, direction);
+                assert_ne!(Tile::Void, input.tile(next));
+                assert_eq!(
+                    (position, direction.reverse()),
+                    cube.wrap(next, facing.reverse())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fold_other_nets() {
+        let open = |c| match c {
+            ' ' => Some(Tile::Void),
+            _ => Some(Tile::Open),
+        };
+        // The layout of the real input, a cross, a staircase and two rows of three faces all
+        // fold into a cube.
+        let nets = [
+            " ##\n # \n## \n#  ",
+            " # \n###\n # \n # ",
+            "#  \n## \n ##\n  #",
+            "###  \n  ###",
+        ];
+        for net in nets {
+            let map = Grid::parse(22, net, open).unwrap();
+            assert!(Cube::fold(&map).is_some(), "{}", net);
+        }
+        // These have the right area, but overlap when folded or are not connected.
+        for net in ["###\n###", "####  \n   ## ", "## \n  #\n###"] {
+            let map = Grid::parse(22, net, open).unwrap();
+            assert!(Cube::fold(&map).is_none(), "{}", net);
+        }
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(6032, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(5031), part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+                    ...#
+                    .#..
+                    #...
+                    ....
+            ...#.......#
+            ........#...
+            ..#....#....
+            ..........#.
+                    ...#....
+                    .....#..
+                    .#......
+                    ......#.
+
+            10R5L5R10L4R5L5
+        "}
+    }
+}
@@ -0,0 +1,313 @@
+use crate::error::ParseError;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::fmt;
+
+// The order in which the elves consider the directions in the first round. Every round starts
+// with the next direction.
+const ORDER: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::West,
+    Direction::East,
+];
+
+// The number of empty tiles added around the elves whenever they get close to the edge of the
+// grid.
+const PADDING: usize = 16;
+
+// The elves on a grid that grows as they spread out, which is much faster to look up than a set
+// of positions.
+#[derive(Debug, Clone)]
+struct Elves {
+    grid: Grid<bool>,
+    positions: Vec<Point<usize>>,
+    rounds: usize,
+}
+
+impl Elves {
+    fn new(positions: &[Point<isize>]) -> Self {
+        let min_x = positions.iter().map(|p| p.x).min().unwrap_or(0);
+        let min_y = positions.iter().map(|p| p.y).min().unwrap_or(0);
+        let positions = positions
+            .iter()
+            .map(|p| Point::new((p.x - min_x) as usize, (p.y - min_y) as usize))
+            .collect();
+        let mut elves = Self {
+            grid: Grid::new(0, 0, false),
+            positions,
+            rounds: 0,
+        };
+        elves.grow();
+        elves
+    }
+
+    // Rebuilds the grid with room for the elves to spread out in every direction.
+    fn grow(&mut self) {
+        let (width, height) = self
+            .positions
+            .iter()
+            .fold((0, 0), |(w, h), p| (w.max(p.x + 1), h.max(p.y + 1)));
+        let (min_x, min_y) = self
+            .positions
+            .iter()
+            .fold((width, height), |(x, y), p| (x.min(p.x), y.min(p.y)));
+        for p in self.positions.iter_mut() {
+            *p = Point::new(p.x - min_x + PADDING, p.y - min_y + PADDING);
+        }
+        self.grid = Grid::new(
+            width - min_x + 2 * PADDING,
+            height - min_y + 2 * PADDING,
+            false,
+        );
+        for p in &self.positions {
+            self.grid[p.to_tuple()] = true;
+        }
+    }
+
+    fn is_elf(&self, p: Point<usize>, delta: Point<isize>) -> bool {
+        let (x, y) = (p.x as isize + delta.x, p.y as isize + delta.y);
+        self.grid[(x as usize, y as usize)]
+    }
+
+    // Returns the position the elf proposes to move to, if any.
+    fn proposal(&self, p: Point<usize>) -> Option<Point<usize>> {
+        let alone = Point::new(0, 0)
+            .neighbours8()
+            .all(|delta| !self.is_elf(p, delta));
+        if alone {
+            return None;
+        }
+        (0..4)
+            .map(|i| ORDER[(self.rounds + i) % 4])
+            .find(|direction| {
+                let delta = direction.delta();
+                [
+                    delta,
+                    delta + direction.turn_left().delta(),
+                    delta + direction.turn_right().delta(),
+                ]
+                .into_iter()
+                .all(|delta| !self.is_elf(p, delta))
+            })
+            .map(|direction| {
+                let delta = direction.delta();
+                Point::new(
+                    (p.x as isize + delta.x) as usize,
+                    (p.y as isize + delta.y) as usize,
+                )
+            })
+    }
+
+    // Plays a round and returns whether any elf moved.
+    fn round(&mut self) -> bool {
+        // Every elf looks at its neighbours, so there must be an empty tile around all of them.
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let near_edge = self
+            .positions
+            .iter()
+            .any(|p| p.x == 0 || p.y == 0 || p.x == width - 1 || p.y == height - 1);
+        if near_edge {
+            self.grow();
+        }
+
+        let proposals = self
+            .positions
+            .iter()
+            .map(|&p| self.proposal(p))
+            .collect::<Vec<Option<Point<usize>>>>();
+        let mut counts = Grid::new(self.grid.width(), self.grid.height(), 0u8);
+        for p in proposals.iter().flatten() {
+            counts[p.to_tuple()] += 1;
+        }
+
+        let mut moved = false;
+        for (position, proposal) in self.positions.iter_mut().zip(proposals) {
+            if let Some(p) = proposal.filter(|p| counts[p.to_tuple()] == 1) {
+                self.grid[position.to_tuple()] = false;
+                self.grid[p.to_tuple()] = true;
+                *position = p;
+                moved = true;
+            }
+        }
+        self.rounds += 1;
+        moved
+    }
+
+    // Returns the smallest and largest corner of the rectangle that contains all the elves.
+    fn bounds(&self) -> Option<(Point<usize>, Point<usize>)> {
+        self.positions.iter().fold(None, |bounds, &p| match bounds {
+            None => Some((p, p)),
+            Some((min, max)) => Some((
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )),
+        })
+    }
+
+    fn empty_tiles(&self) -> usize {
+        match self.bounds() {
+            Some((min, max)) => (max.x - min.x + 1) * (max.y - min.y + 1) - self.positions.len(),
+            None => 0,
+        }
+    }
+
+    // Renders the rectangle that contains all the elves, in the same format as the puzzle input.
+    fn render(&self) -> String {
+        let Some((min, max)) = self.bounds() else {
+            return String::new();
+        };
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| if self.grid[(x, y)] { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// Shows the elves after the rounds played so far, e.g. `println!("{}", elves)`.
+impl fmt::Display for Elves {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+#[aoc_generator(day23)]
+fn parse_input(input: &str) -> Result<Vec<Point<isize>>, ParseError> {
+    let input = normalise(input);
+    let grid = Grid::parse(23, &input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })?;
+    Ok(grid
+        .positions()
+        .filter(|&p| grid[p])
+        .map(|(x, y)| Point::new(x as isize, y as isize))
+        .collect())
+}
+
+#[aoc(day23, part1)]
+fn part1(positions: &[Point<isize>]) -> usize {
+    let mut elves = Elves::new(positions);
+    for _ in 0..10 {
+        elves.round();
+    }
+    elves.empty_tiles()
+}
+
+#[aoc(day23, part2)]
+fn part2(positions: &[Point<isize>]) -> usize {
+    let mut elves = Elves::new(positions);
+    while elves.round() {}
+    elves.rounds
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(23, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(22, input.len());
+        assert_eq!(Point::new(4, 0), input[0]);
+        assert_eq!(Point::new(4, 6), input[21]);
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("#.###..", "#.#x#..");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(23, 5, 4, "#.#x#..", "invalid character"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_small_example() {
+        let input = parse_input(".....\n..##.\n..#..\n.....\n..##.\n.....").unwrap();
+        let mut elves = Elves::new(&input);
+        let expected = [
+            "##\n..\n#.\n.#\n#.",
+            ".##.\n#...\n...#\n....\n.#..",
+            "..#..\n....#\n#....\n....#\n.....\n..#..",
+        ];
+        for expected in expected {
+            assert!(elves.round());
+            assert_eq!(expected, elves.render());
+        }
+        assert!(!elves.round());
+        assert_eq!(4, elves.rounds);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = parse_input(get_test_input()).unwrap();
+        let mut elves = Elves::new(&input);
+        assert_eq!(get_test_input().trim_end(), elves.render());
+        for _ in 0..10 {
+            elves.round();
+        }
+        // The puzzle description shows an empty border around the elves, which is left out.
+        let expected = indoc! {"
+            ......#.....
+            ..........#.
+            .#.#..#.....
+            .....#......
+            ..#.....#..#
+            #......##...
+            ....##......
+            .#........#.
+            ...#.#..#...
+            ............
+            ...#..#..#..
+        "};
+        assert_eq!(expected.trim_end(), elves.to_string());
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(110, part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(20, part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            ....#..
+            ..###.#
+            #...#.#
+            .#...##
+            #.###..
+            ##.#.##
+            .#..#..
+        "}
+    }
+}
@@ -0,0 +1,283 @@
+use crate::error::ParseError;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use pathfinding::prelude::bfs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Wall,
+    Open,
+    Blizzard(Direction),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Valley {
+    // The blizzards inside the walls at the start, by the direction they move in.
+    blizzards: Grid<Option<Direction>>,
+    // The positions just outside the walls, relative to the top left corner inside the walls.
+    start: Point<isize>,
+    goal: Point<isize>,
+    // The number of minutes after which all the blizzards are back where they started.
+    period: usize,
+}
+
+impl Valley {
+    // Returns whether a blizzard covers the position inside the walls at the given time.
+    //
+    // Every blizzard moves in a straight line and wraps around, so instead of simulating them,
+    // look at the positions the blizzards would have had to start from to be here now.
+    fn is_blizzard(&self, x: usize, y: usize, time: usize) -> bool {
+        let (width, height) = (self.blizzards.width(), self.blizzards.height());
+        let (dx, dy) = (time % width, time % height);
+        self.blizzards[((x + width - dx) % width, y)] == Some(Direction::East)
+            || self.blizzards[((x + dx) % width, y)] == Some(Direction::West)
+            || self.blizzards[(x, (y + height - dy) % height)] == Some(Direction::South)
+            || self.blizzards[(x, (y + dy) % height)] == Some(Direction::North)
+    }
+
+    fn is_safe(&self, position: Point<isize>, time: usize) -> bool {
+        if position == self.start || position == self.goal {
+            return true;
+        }
+        match position.to_unsigned() {
+            Some(p) if p.x < self.blizzards.width() && p.y < self.blizzards.height() => {
+                !self.is_blizzard(p.x, p.y, time)
+            }
+            _ => false,
+        }
+    }
+
+    // Returns the number of minutes it takes to get from one side of the valley to the other,
+    // starting at the given time, or `None` if the blizzards block every way.
+    //
+    // The blizzards repeat with the period, so the search runs over the positions combined with
+    // the time within the period. Waiting in place is one of the moves.
+    fn crossing(&self, from: Point<isize>, to: Point<isize>, time: usize) -> Option<usize> {
+        let path = bfs(
+            &(from, time % self.period),
+            |&(position, time)| {
+                let time = (time + 1) % self.period;
+                Direction::VALUES
+                    .iter()
+                    .map(move |d| position + d.delta())
+                    .chain([position])
+                    .filter(move |&p| self.is_safe(p, time))
+                    .map(move |p| (p, time))
+            },
+            |&(position, _)| position == to,
+        )?;
+        Some(path.len() - 1)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[aoc_generator(day24)]
+fn parse_input(input: &str) -> Result<Valley, ParseError> {
+    let input = normalise(input);
+    let grid = Grid::parse(24, &input, |c| match c {
+        '#' => Some(Tile::Wall),
+        '.' => Some(Tile::Open),
+        '^' => Some(Tile::Blizzard(Direction::North)),
+        '>' => Some(Tile::Blizzard(Direction::East)),
+        'v' => Some(Tile::Blizzard(Direction::South)),
+        '<' => Some(Tile::Blizzard(Direction::West)),
+        _ => None,
+    })?;
+    let lines = input.lines().collect::<Vec<&str>>();
+    let (width, height) = (grid.width(), grid.height());
+    if width < 3 || height < 3 {
+        return Err(ParseError::new(24, 1, 1, lines[0], "valley too small"));
+    }
+
+    // The valley is surrounded by walls, except for a single opening at the top and bottom.
+    let opening = |y: usize| {
+        let error = |x: usize, reason| ParseError::new(24, y + 1, x + 1, lines[y], reason);
+        let mut openings = (0..width).filter(|&x| grid[(x, y)] != Tile::Wall);
+        let x = openings
+            .next()
+            .ok_or_else(|| error(0, "missing opening in the wall"))?;
+        if let Some(other) = openings.next() {
+            return Err(error(other, "expected a single opening in the wall"));
+        }
+        if grid[(x, y)] != Tile::Open || x == 0 || x == width - 1 {
+            return Err(error(x, "invalid opening in the wall"));
+        }
+        Ok(x as isize - 1)
+    };
+    let start = Point::new(opening(0)?, -1);
+    let goal = Point::new(opening(height - 1)?, height as isize - 2);
+
+    let blizzards = Grid::from_fn(width - 2, height - 2, |x, y| match grid[(x + 1, y + 1)] {
+        Tile::Blizzard(direction) => Some(direction),
+        _ => None,
+    });
+    for y in 1..height - 1 {
+        let error = |x: usize, reason| ParseError::new(24, y + 1, x + 1, lines[y], reason);
+        for x in 0..width {
+            let tile = grid[(x, y)];
+            match tile {
+                Tile::Wall if x > 0 && x < width - 1 => return Err(error(x, "unexpected wall")),
+                Tile::Open | Tile::Blizzard(_) if x == 0 || x == width - 1 => {
+                    return Err(error(x, "expected a wall"));
+                }
+                // A blizzard that moves in line with an opening would leave the valley.
+                Tile::Blizzard(Direction::North | Direction::South)
+                    if x as isize - 1 == start.x || x as isize - 1 == goal.x =>
+                {
+                    return Err(error(x, "blizzard leaves the valley"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let period =
+        blizzards.width() / gcd(blizzards.width(), blizzards.height()) * blizzards.height();
+    Ok(Valley {
+        blizzards,
+        start,
+        goal,
+        period,
+    })
+}
+
+#[aoc(day24, part1)]
+fn part1(valley: &Valley) -> Option<usize> {
+    valley.crossing(valley.start, valley.goal, 0)
+}
+
+#[aoc(day24, part2)]
+fn part2(valley: &Valley) -> Option<usize> {
+    // Go to the goal, back to the start for the snacks, and to the goal again.
+    let there = valley.crossing(valley.start, valley.goal, 0)?;
+    let back = valley.crossing(valley.goal, valley.start, there)?;
+    let again = valley.crossing(valley.start, valley.goal, there + back)?;
+    Some(there + back + again)
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(24, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!((6, 4), (input.blizzards.width(), input.blizzards.height()));
+        assert_eq!(Point::new(0, -1), input.start);
+        assert_eq!(Point::new(5, 4), input.goal);
+        assert_eq!(12, input.period);
+        assert_eq!(Some(Direction::East), input.blizzards[(0, 0)]);
+        assert_eq!(Some(Direction::West), input.blizzards[(1, 1)]);
+        assert_eq!(Some(Direction::South), input.blizzards[(1, 2)]);
+        assert_eq!(Some(Direction::North), input.blizzards[(3, 3)]);
+        assert_eq!(None, input.blizzards[(2, 0)]);
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("#<^v^^>#", "#<^#^^>#");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(24, 5, 4, "#<^#^^>#", "unexpected wall"),
+            error
+        );
+
+        let input = get_test_input().replace("#.######", "#.##.###");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                24,
+                1,
+                5,
+                "#.##.###",
+                "expected a single opening in the wall"
+            ),
+            error
+        );
+
+        let input = get_test_input().replace("######.#", "########");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(24, 6, 1, "########", "missing opening in the wall"),
+            error
+        );
+
+        let input = get_test_input().replace("#>>.<^<#", "#v>.<^<#");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(24, 2, 2, "#v>.<^<#", "blizzard leaves the valley"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_blizzards() {
+        let valley = parse_input(indoc! {"
+            #.#####
+            #.....#
+            #>....#
+            #.....#
+            #...v.#
+            #.....#
+            #####.#
+        "})
+        .unwrap();
+        assert_eq!(5, valley.period);
+        assert!(valley.is_blizzard(0, 1, 0));
+        assert!(valley.is_blizzard(3, 3, 0));
+        // After three minutes, both blizzards are in the same position.
+        assert!(valley.is_blizzard(3, 1, 3));
+        assert!(!valley.is_blizzard(0, 1, 3));
+        assert!(!valley.is_blizzard(3, 3, 3));
+        // The blizzard moving down wraps around to the top.
+        assert!(valley.is_blizzard(3, 0, 2));
+        assert!(valley.is_blizzard(0, 1, 5));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(18), part1(&input));
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(54), part2(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            #.######
+            #>>.<^<#
+            #.<..<<#
+            #>v.><>#
+            #<^v^^>#
+            ######.#
+        "}
+    }
+}
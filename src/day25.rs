@@ -0,0 +1,316 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
+use aoc_runner_derive::{aoc, aoc_generator};
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
+// A number in the "Special Numeral-Analogue Fuel Units" notation: balanced base five, where the
+// digits `=`, `-`, `0`, `1` and `2` stand for -2 to 2. Negative numbers need no sign.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Snafu(i64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParseSnafuError {
+    // The 0-based index of the offending character.
+    index: usize,
+    reason: &'static str,
+}
+
+impl fmt::Display for ParseSnafuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at index {}", self.reason, self.index)
+    }
+}
+
+impl Error for ParseSnafuError {}
+
+impl FromStr for Snafu {
+    type Err = ParseSnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseSnafuError {
+                index: 0,
+                reason: "empty number",
+            });
+        }
+        // Intermediate values can be larger than the result, e.g. "2=" is 10 - 2.
+        let mut value: i128 = 0;
+        for (index, c) in s.chars().enumerate() {
+            let error = |reason| ParseSnafuError { index, reason };
+            let digit = match c {
+                '=' => -2,
+                '-' => -1,
+                '0' => 0,
+                '1' => 1,
+                '2' => 2,
+                _ => return Err(error("invalid digit")),
+            };
+            value = value
+                .checked_mul(5)
+                .and_then(|v| v.checked_add(digit))
+                .ok_or_else(|| error("number too large"))?;
+        }
+        let value = i64::try_from(value).map_err(|_| ParseSnafuError {
+            index: s.len() - 1,
+            reason: "number too large",
+        })?;
+        Ok(Snafu(value))
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0");
+        }
+        // Take off the least significant digit each time. The digits -2 and -1 are written in
+        // place of 3 and 4, which carries one over to the next digit. The value is widened so the
+        // carry cannot overflow.
+        let mut value = self.0 as i128;
+        let mut digits = vec![];
+        while value != 0 {
+            let (digit, c) = match value.rem_euclid(5) {
+                0 => (0, '0'),
+                1 => (1, '1'),
+                2 => (2, '2'),
+                3 => (-2, '='),
+                _ => (-1, '-'),
+            };
+            digits.push(c);
+            value = (value - digit) / 5;
+        }
+        write!(f, "{}", digits.iter().rev().collect::<String>())
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(value: i64) -> Self {
+        Snafu(value)
+    }
+}
+
+impl From<Snafu> for i64 {
+    fn from(snafu: Snafu) -> Self {
+        snafu.0
+    }
+}
+
+impl Snafu {
+    fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Snafu)
+    }
+}
+
+// Panics if the sum does not fit, in release builds too. Use `checked_add` to handle it.
+impl Add for Snafu {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("SNAFU number overflow")
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+// Sums the numbers, or returns `None` if the sum does not fit.
+impl Sum<Snafu> for Option<Snafu> {
+    fn sum<I: Iterator<Item = Snafu>>(mut iter: I) -> Self {
+        iter.try_fold(Snafu::default(), Snafu::checked_add)
+    }
+}
+
+#[aoc_generator(day25)]
+fn parse_input(input: &str) -> Result<Vec<Snafu>, ParseError> {
+    let input = normalise(input);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse().map_err(|e: ParseSnafuError| {
+                ParseError::new(25, i + 1, e.index + 1, line, e.reason)
+            })
+        })
+        .collect()
+}
+
+#[aoc(day25, part1)]
+fn part1(numbers: &[Snafu]) -> Option<String> {
+    numbers
+        .iter()
+        .copied()
+        .sum::<Option<Snafu>>()
+        .map(|sum| sum.to_string())
+}
+
+// The last day only has a single puzzle. The second star is awarded for all the others.
+#[aoc(day25, part2)]
+fn part2(_: &[Snafu]) -> String {
+    "Merry Christmas!".to_string()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(25, parse_input, part1, parse_input, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::variants;
+    use indoc::indoc;
+
+    // The conversions listed in the puzzle description.
+    const EXAMPLES: [(i64, &str); 26] = [
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (6, "11"),
+        (7, "12"),
+        (8, "2="),
+        (9, "2-"),
+        (10, "20"),
+        (15, "1=0"),
+        (20, "1-0"),
+        (2022, "1=11-2"),
+        (12345, "1-0---0"),
+        (314159265, "1121-1110-1=0"),
+        (1747, "1=-0-2"),
+        (906, "12111"),
+        (198, "2=0="),
+        (11, "21"),
+        (201, "2=01"),
+        (31, "111"),
+        (1257, "20012"),
+        (32, "112"),
+        (353, "1=-1="),
+        (107, "1-12"),
+        (37, "122"),
+    ];
+
+    #[test]
+    fn test_parse_input() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(13, input.len());
+        assert_eq!(Snafu(1747), input[0]);
+        assert_eq!(Snafu(37), input[12]);
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("2=0=", "2=3=");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(ParseError::new(25, 3, 3, "2=3=", "invalid digit"), error);
+
+        let too_large = "2".repeat(28);
+        let error = parse_input(&too_large).unwrap_err();
+        assert_eq!(
+            ParseError::new(25, 1, 28, &too_large, "number too large"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        for (decimal, snafu) in EXAMPLES {
+            assert_eq!(Ok(Snafu(decimal)), snafu.parse(), "{}", snafu);
+            assert_eq!(snafu, Snafu::from(decimal).to_string(), "{}", decimal);
+        }
+        assert_eq!("0", Snafu(0).to_string());
+        assert_eq!("-", Snafu(-1).to_string());
+        assert_eq!("-2", Snafu(-3).to_string());
+        assert!("".parse::<Snafu>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut values = (-5000..=5000).collect::<Vec<i64>>();
+        values.extend([i64::MIN, i64::MIN + 1, i64::MAX - 1, i64::MAX]);
+        // Pseudo-random values across the whole range.
+        let mut seed = 12345u64;
+        for _ in 0..10000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            values.push(seed as i64);
+        }
+        for value in values {
+            let snafu = Snafu::from(value);
+            let text = snafu.to_string();
+            assert_eq!(Ok(snafu), text.parse(), "{} as {}", value, text);
+            assert_eq!(value, i64::from(snafu));
+            // Leading zeros are never written, so the representation is unique.
+            assert!(value == 0 || !text.starts_with('0'), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_sum() {
+        let numbers = [Snafu(3), Snafu(-10), Snafu(2022)];
+        assert_eq!(Snafu(2015), numbers.iter().sum());
+        assert_eq!(Snafu(2015), numbers.into_iter().sum());
+        assert_eq!(Snafu(-7), Snafu(3) + Snafu(-10));
+        assert_eq!(Snafu(0), [].iter().sum());
+        assert_eq!(Some(Snafu(2015)), numbers.into_iter().sum());
+        assert_eq!(
+            None,
+            [Snafu(i64::MAX), Snafu(1)]
+                .into_iter()
+                .sum::<Option<Snafu>>()
+        );
+        assert_eq!(None, Snafu(i64::MAX).checked_add(Snafu(1)));
+        assert_eq!(Some(Snafu(i64::MAX)), Snafu(i64::MAX).checked_add(Snafu(0)));
+    }
+
+    #[test]
+    fn part1_example() {
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some("2=-1=0".to_string()), part1(&input));
+
+        // The sum of the fuel requirements must fit as well.
+        let large = Snafu(i64::MAX).to_string();
+        let input = parse_input(&format!("{}\n1\n", large)).unwrap();
+        assert_eq!(None, part1(&input));
+    }
+
+    fn get_test_input<'a>() -> &'a str {
+        indoc! {"
+            1=-0-2
+            12111
+            2=0=
+            21
+            2=01
+            111
+            20012
+            112
+            1=-1=
+            1-12
+            12
+            1=
+            122
+        "}
+    }
+}
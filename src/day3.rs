@@ -1,65 +1,85 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::cmp::Ordering;
 
 #[aoc_generator(day3)]
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|l| l.chars().collect()).collect()
+fn parse_input(input: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    let input = normalise(input);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            // Item types are identified by a single ASCII letter.
+            match l.chars().position(|c| !c.is_ascii_alphabetic()) {
+                Some(pos) => Err(ParseError::new(3, i + 1, pos + 1, l, "invalid item type")),
+                None => Ok(l.chars().collect()),
+            }
+        })
+        .collect()
+}
+
+// Returns the priority of an item type.
+fn priority(item: char) -> usize {
+    // Convert the char to its ASCII value.
+    let item = item as usize;
+    match item.cmp(&96) {
+        // If the char is a lowercase letter, subtract 96.
+        Ordering::Greater => item - 96,
+        // If the char is an uppercase letter, subtract 38.
+        _ => item - 38,
+    }
 }
 
+// Returns `None` if a rucksack cannot be split into two equal compartments, or if its compartments
+// have no item type in common.
 #[aoc(day3, part1)]
-fn part1(contents: &[Vec<char>]) -> usize {
+fn part1(contents: &[Vec<char>]) -> Option<usize> {
     contents
         .iter()
         .map(|content| {
+            if content.len() % 2 != 0 {
+                return None;
+            }
             // Split the content in two equal parts.
             let (left, right) = content.split_at(content.len() / 2);
-            // Filter out all chars which do not exist in both parts.
-            let common = left
-                .iter()
-                .filter(|&c| right.contains(c))
-                .collect::<Vec<_>>();
-            // Keep only the first common char.
-            let common = common.first().unwrap();
-            // Convert the char to its ASCII value.
-            let common = **common as usize;
-            match common.cmp(&96) {
-                // If the char is a lowercase letter, subtract 96.
-                Ordering::Greater => common - 96,
-                // If the char is an uppercase letter, subtract 64.
-                _ => common - 38,
-            }
+            // Keep only the first char which exists in both parts.
+            left.iter()
+                .find(|&c| right.contains(c))
+                .map(|&common| priority(common))
         })
         .sum()
 }
 
+// Returns `None` if the elves cannot be divided into groups of three, or if a group has no item
+// type in common.
 #[aoc(day3, part2)]
-fn part2(contents: &[Vec<char>]) -> usize {
+fn part2(contents: &[Vec<char>]) -> Option<usize> {
+    if contents.len() % 3 != 0 {
+        return None;
+    }
     // Loop over the contents in batches of 3.
     contents
         .chunks(3)
         .map(|content| {
-            // Find the chars which are in common.
-            let common = content[0]
+            // Keep only the first char which is in common.
+            content[0]
                 .iter()
-                .filter(|&c| content[1].contains(c) && content[2].contains(c))
-                .collect::<Vec<_>>();
-            // Keep only the first common char.
-            let common = common.first().unwrap();
-            // Convert the char to its ASCII value.
-            let common = **common as usize;
-            match common.cmp(&96) {
-                // If the char is a lowercase letter, subtract 96.
-                Ordering::Greater => common - 96,
-                // If the char is an uppercase letter, subtract 64.
-                _ => common - 38,
-            }
+                .find(|&c| content[1].contains(c) && content[2].contains(c))
+                .map(|&common| priority(common))
         })
         .sum()
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(3, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -77,19 +97,53 @@ mod tests {
             String::from("CrZsJsPPZsGzwwsLwLmpwMDw").chars().collect(),
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("vJrwpWtwJgWr\nhcsF-MMf\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(3, 2, 5, "hcsF-MMf", "invalid item type"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_solve_invalid() {
+        // The compartments have no item type in common.
+        let input = parse_input("abcd\nabca\nabcb\n").unwrap();
+        assert_eq!(None, part1(&input));
+        // The compartments have different sizes.
+        let input = parse_input("aba\n").unwrap();
+        assert_eq!(None, part1(&input));
+
+        // The group has no badge.
+        let input = parse_input("abcd\nefgh\nijkl\n").unwrap();
+        assert_eq!(None, part2(&input));
+        // The last group is incomplete.
+        let input = parse_input(&get_test_input().replace("CrZsJsPPZsGzwwsLwLmpwMDw\n", ""));
+        assert_eq!(None, part2(&input.unwrap()));
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(157, part1(&input));
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(157), part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(70, part2(&input));
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(70), part2(&input));
     }
 
     fn get_test_input<'a>() -> &'a str {
@@ -1,3 +1,6 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
 
@@ -22,22 +25,34 @@ impl Assignment {
 }
 
 #[aoc_generator(day4)]
-fn parse_input(input: &str) -> Vec<(Assignment, Assignment)> {
+fn parse_input(input: &str) -> Result<Vec<(Assignment, Assignment)>, ParseError> {
+    let input = normalise(input);
     let re = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
     input
         .lines()
-        .map(|l| {
-            let caps = re.captures(l).unwrap();
-            (
+        .enumerate()
+        .map(|(i, l)| {
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| ParseError::new(4, i + 1, 1, l, "invalid assignment pair"))?;
+            // The section IDs are matched by the regex, so they can only fail to parse if they
+            // are out of range.
+            let section = |n: usize| {
+                let m = caps.get(n).unwrap();
+                m.as_str()
+                    .parse()
+                    .map_err(|_| ParseError::new(4, i + 1, m.start() + 1, l, "invalid section ID"))
+            };
+            Ok((
                 Assignment {
-                    start: caps[1].parse().unwrap(),
-                    end: caps[2].parse().unwrap(),
+                    start: section(1)?,
+                    end: section(2)?,
                 },
                 Assignment {
-                    start: caps[3].parse().unwrap(),
-                    end: caps[4].parse().unwrap(),
+                    start: section(3)?,
+                    end: section(4)?,
                 },
-            )
+            ))
         })
         .collect()
 }
@@ -58,9 +73,14 @@ fn part2(assignment_pairs: &[(Assignment, Assignment)]) -> usize {
         .count()
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(4, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -92,18 +112,41 @@ mod tests {
             ),
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("2-4,6-8\n2-3;4-5\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(4, 2, 1, "2-3;4-5", "invalid assignment pair"),
+            error
+        );
+
+        let error = parse_input("2-4,6-800\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(4, 1, 7, "2-4,6-800", "invalid section ID"),
+            error
+        );
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(2, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(4, part2(&input));
     }
 
@@ -1,3 +1,6 @@
+use crate::error::ParseError;
+use crate::input::{normalise, paragraphs};
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
 
@@ -55,6 +58,7 @@ impl Storage {
         }
     }
 
+    // The generator checked that every operation has enough crates to move.
     fn perform(&mut self, operation: &Operation) {
         for _ in 0..operation.quantity {
             let item = self.pop(operation.from).unwrap();
@@ -62,6 +66,15 @@ impl Storage {
         }
     }
 
+    // Returns the crate on top of every stack, or `None` if a stack is empty.
+    fn top_crates(&self) -> Option<String> {
+        self.stacks
+            .iter()
+            .map(|s| s.items.last().cloned())
+            .collect::<Option<Vec<String>>>()
+            .map(|items| items.join(""))
+    }
+
     fn move_stack(&mut self, operation: &Operation) {
         let mut items = Vec::new();
         for _ in 0..operation.quantity {
@@ -83,46 +96,100 @@ struct Operation {
 }
 
 #[aoc_generator(day5)]
-fn parse_input(input: &str) -> (Storage, Vec<Operation>) {
+fn parse_input(input: &str) -> Result<(Storage, Vec<Operation>), ParseError> {
+    let input = normalise(input);
     // Split the input by empty lines.
-    let input: Vec<&str> = input.split("\n\n").collect();
+    let paragraphs = paragraphs(&input);
+    if let Some((line, text)) = paragraphs.get(2) {
+        let text = text.lines().next().unwrap();
+        return Err(ParseError::new(
+            5,
+            line + 1,
+            1,
+            text,
+            "unexpected empty line",
+        ));
+    }
+    let [(storage_line, storage_input), (operations_line, operations_input)] = paragraphs[..]
+    else {
+        let reason = "missing empty line after the stack drawing";
+        return Err(ParseError::new(5, 1, 1, "", reason));
+    };
 
     // Parse the storage.
     let mut storage = Storage::new();
-    let mut storage_input = input[0].lines().collect::<Vec<&str>>();
-    storage_input.pop();
-
-    for line in storage_input {
-        line.chars()
-            .skip(1)
-            .step_by(4)
-            .enumerate()
-            .for_each(|(i, c)| {
-                if c != ' ' {
-                    storage.push(i, c.to_string());
+    let mut storage_input = storage_input.lines().collect::<Vec<&str>>();
+    // The last line numbers the stacks, including those that start out empty.
+    let labels = storage_input.pop().unwrap_or_default();
+    storage
+        .stacks
+        .resize(labels.split_whitespace().count(), Stack::new());
+
+    for (i, line) in storage_input.iter().enumerate() {
+        for (j, c) in line.chars().skip(1).step_by(4).enumerate() {
+            match c {
+                ' ' => {}
+                c if c.is_ascii_uppercase() => storage.push(j, c.to_string()),
+                _ => {
+                    let line_number = storage_line + i + 1;
+                    return Err(ParseError::new(
+                        5,
+                        line_number,
+                        j * 4 + 2,
+                        line,
+                        "invalid crate",
+                    ));
                 }
-            });
+            }
+        }
     }
 
     storage.reverse();
 
+    // The number of crates on every stack, to check that each operation has enough crates to
+    // move. Both parts move the same number of crates, so the heights are the same for both.
+    let mut heights = storage
+        .stacks
+        .iter()
+        .map(|s| s.items.len())
+        .collect::<Vec<usize>>();
+    let line_offset = operations_line + 1;
     let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)").unwrap();
-    let operations = input[1]
+    let operations = operations_input
         .lines()
-        .map(|l| {
-            let caps = re.captures(l).unwrap();
-            Operation {
-                quantity: caps[1].parse().unwrap(),
-                from: caps[2].parse::<usize>().unwrap() - 1,
-                to: caps[3].parse::<usize>().unwrap() - 1,
+        .enumerate()
+        .map(|(i, l)| {
+            let error = |column, reason| ParseError::new(5, i + line_offset, column, l, reason);
+            let caps = re
+                .captures(l)
+                .ok_or_else(|| error(1, "invalid operation"))?;
+            let quantity = caps[1]
+                .parse()
+                .map_err(|_| error(caps.get(1).unwrap().start() + 1, "invalid quantity"))?;
+            // Convert the 1-based stack numbers to indexes, and check they refer to an existing
+            // stack.
+            let stack = |n: usize| {
+                let m = caps.get(n).unwrap();
+                match m.as_str().parse::<usize>() {
+                    Ok(number) if (1..=storage.stacks.len()).contains(&number) => Ok(number - 1),
+                    _ => Err(error(m.start() + 1, "unknown stack")),
+                }
+            };
+            let (from, to) = (stack(2)?, stack(3)?);
+            if heights[from] < quantity {
+                let column = caps.get(1).unwrap().start() + 1;
+                return Err(error(column, "not enough crates on the stack"));
             }
+            heights[from] -= quantity;
+            heights[to] += quantity;
+            Ok(Operation { quantity, from, to })
         })
-        .collect();
-    (storage, operations)
+        .collect::<Result<Vec<Operation>, ParseError>>()?;
+    Ok((storage, operations))
 }
 
 #[aoc(day5, part1)]
-fn part1(procedure: &(Storage, Vec<Operation>)) -> String {
+fn part1(procedure: &(Storage, Vec<Operation>)) -> Option<String> {
     let (storage, operations) = procedure;
     let mut storage = storage.clone();
 
@@ -130,16 +197,11 @@ fn part1(procedure: &(Storage, Vec<Operation>)) -> String {
         storage.perform(op);
     });
 
-    storage
-        .stacks
-        .iter()
-        .map(|s| s.items.last().unwrap().clone())
-        .collect::<Vec<String>>()
-        .join("")
+    storage.top_crates()
 }
 
 #[aoc(day5, part2)]
-fn part2(procedure: &(Storage, Vec<Operation>)) -> String {
+fn part2(procedure: &(Storage, Vec<Operation>)) -> Option<String> {
     let (storage, operations) = procedure;
     let mut storage = storage.clone();
 
@@ -147,17 +209,17 @@ fn part2(procedure: &(Storage, Vec<Operation>)) -> String {
         storage.move_stack(op);
     });
 
-    storage
-        .stacks
-        .iter()
-        .map(|s| s.items.last().unwrap().clone())
-        .collect::<Vec<String>>()
-        .join("")
+    storage.top_crates()
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(5, parse_input, part1, parse_input, part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -200,19 +262,77 @@ mod tests {
             ],
         );
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let input = get_test_input().replace("move 3 from 1 to 3", "move 3 from 1 to 4");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(5, 7, 18, "move 3 from 1 to 4", "unknown stack"),
+            error
+        );
+
+        let input = get_test_input().replace("[M]", "[m]");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(5, 3, 6, "[Z] [m] [P]", "invalid crate"),
+            error
+        );
+
+        // Stack 1 only holds two crates after the first operation.
+        let input = get_test_input().replace("move 3 from 1 to 3", "move 4 from 1 to 3");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(
+                5,
+                7,
+                6,
+                "move 4 from 1 to 3",
+                "not enough crates on the stack"
+            ),
+            error
+        );
+
+        let error = parse_input("[A]\n 1 \nmove 1 from 1 to 1\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(5, 1, 1, "", "missing empty line after the stack drawing"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_solve_invalid() {
+        // All crates end up on the first stack, so the second one has no top crate.
+        let input = parse_input("    [B]\n[A] [C]\n 1   2 \n\nmove 2 from 2 to 1\n").unwrap();
+        assert_eq!(None, part1(&input));
+        assert_eq!(None, part2(&input));
+
+        // The second stack starts out empty, but crates can still be moved onto it.
+        let input = parse_input("[A]\n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+        assert_eq!(2, input.0.stacks.len());
+        assert_eq!(None, part1(&input));
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!("CMZ".to_string(), part1(&input));
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some("CMZ".to_string()), part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!("MCD", part2(&input));
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some("MCD".to_string()), part2(&input));
     }
 
     fn get_test_input<'a>() -> &'a str {
@@ -1,40 +1,64 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day6)]
-fn parse_input(input: &str) -> String {
-    input.to_string()
+fn parse_input(input: &str) -> Result<String, ParseError> {
+    let input = normalise(input);
+    // The datastream consists of lowercase letters only. Since the markers are found by slicing
+    // the string, multibyte characters would cause a panic later on.
+    for (i, line) in input.lines().enumerate() {
+        if let Some(pos) = line.chars().position(|c| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(
+                6,
+                i + 1,
+                pos + 1,
+                line,
+                "invalid character",
+            ));
+        }
+    }
+    Ok(input.to_string())
 }
 
 #[aoc(day6, part1)]
-fn part1(datastream: &str) -> usize {
+fn part1(datastream: &str) -> Option<usize> {
     find_non_consecutive(datastream, 4)
 }
 
 #[aoc(day6, part2)]
-fn part2(datastream: &str) -> usize {
+fn part2(datastream: &str) -> Option<usize> {
     find_non_consecutive(datastream, 14)
 }
 
-fn find_non_consecutive(datastream: &str, count: usize) -> usize {
+// Returns the number of characters read up to the end of the first window of `count` different
+// characters, or `None` if there is no such window.
+fn find_non_consecutive(datastream: &str, count: usize) -> Option<usize> {
     // Loop through the datastream as a moving window of usize characters.
-    for i in 0..datastream.len() - count + 1 {
+    for i in 0..(datastream.len() + 1).saturating_sub(count) {
         let window = &datastream[i..i + count];
         // Check that there are no duplicate characters in the window.
         if window.chars().all(|c| window.matches(c).count() == 1) {
-            return i + count;
+            return Some(i + count);
         }
     }
 
-    unreachable!()
+    None
+}
+
+pub fn solver() -> DaySolver {
+    DaySolver::new(6, parse_input, part1, parse_input, part2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
 
     #[test]
     fn test_parse_input() {
-        let expected = vec![
+        let expected = [
             "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string(),
             "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string(),
             "nppdvjthqldpwncqszvftbrmjlhg".to_string(),
@@ -47,15 +71,44 @@ mod tests {
             .enumerate()
             .for_each(|(i, test_case)| {
                 let (input, _) = test_case;
-                assert_eq!(expected[i], parse_input(input));
+                assert_eq!(Ok(expected[i].clone()), parse_input(input));
             });
     }
 
+    #[test]
+    fn test_parse_input_variants() {
+        for (input, _) in get_test_input_part1() {
+            for variant in variants(input) {
+                assert_eq!(parse_input(input), parse_input(&variant));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("mjqjpqmgb€ljsphd").unwrap_err();
+        assert_eq!(
+            ParseError::new(6, 1, 10, "mjqjpqmgb€ljsphd", "invalid character"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_solve_invalid() {
+        // Too short to hold a marker.
+        assert_eq!(None, part1("abc"));
+        assert_eq!(None, part1(""));
+        // Long enough, but every window has a duplicate.
+        assert_eq!(None, part1("abcabcabc"));
+        assert_eq!(Some(4), part1("abcd"));
+        assert_eq!(None, part2("abcdefghijklm"));
+    }
+
     #[test]
     fn part1_example() {
         let test_cases = get_test_input_part1();
         for (input, expected) in test_cases {
-            assert_eq!(expected, part1(&input));
+            assert_eq!(Some(expected), part1(input));
         }
     }
 
@@ -63,7 +116,7 @@ mod tests {
     fn part2_example() {
         let test_cases = get_test_input_part2();
         for (input, expected) in test_cases {
-            assert_eq!(expected, part2(&input));
+            assert_eq!(Some(expected), part2(input));
         }
     }
 
@@ -1,5 +1,9 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, PartialEq)]
 enum NodeType {
@@ -41,11 +45,7 @@ impl Node {
                 .children
                 .iter_mut()
                 .find(|c| c.name == path[1] && c.node_type == NodeType::Directory);
-            if let Some(child) = child {
-                child.find_by_path_mut(&path[1..])
-            } else {
-                panic!("Child not found");
-            }
+            child?.find_by_path_mut(&path[1..])
         }
     }
 
@@ -67,8 +67,55 @@ impl Node {
 }
 
 #[aoc_generator(day7)]
-fn parse_input(input: &str) -> Vec<String> {
-    input.lines().map(|l| l.to_string()).collect()
+fn parse_input(input: &str) -> Result<Vec<String>, ParseError> {
+    let input = normalise(input);
+    let re = Regex::new(r"^(\d+) (.+)$").unwrap();
+    // The directories listed so far, by their path, to check that every `cd` goes to a directory
+    // that exists.
+    let mut path = vec!["/"];
+    let mut directories = HashSet::from([path.clone()]);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let error = |reason| ParseError::new(7, i + 1, 1, l, reason);
+            // Check that the line is either a known command or a directory listing entry.
+            let valid = match l {
+                "$ ls" => true,
+                l if l.starts_with("$ cd ") => l.len() > 5,
+                l if l.starts_with("dir ") => l.len() > 4,
+                l => match re.captures(l) {
+                    Some(caps) => caps[1].parse::<usize>().is_ok(),
+                    None => false,
+                },
+            };
+            if !valid {
+                return Err(error("unrecognized terminal output"));
+            }
+            match l {
+                "$ cd /" => path.truncate(1),
+                "$ cd .." if path.len() == 1 => {
+                    return Err(error("cannot leave the root directory"));
+                }
+                "$ cd .." => {
+                    path.pop();
+                }
+                l if l.starts_with("$ cd ") => {
+                    path.push(&l[5..]);
+                    if !directories.contains(&path) {
+                        return Err(ParseError::new(7, i + 1, 6, l, "unknown directory"));
+                    }
+                }
+                l if l.starts_with("dir ") => {
+                    let mut directory = path.clone();
+                    directory.push(&l[4..]);
+                    directories.insert(directory);
+                }
+                _ => {}
+            }
+            Ok(l.to_string())
+        })
+        .collect()
 }
 
 #[aoc(day7, part1)]
@@ -85,18 +132,17 @@ fn part1(output: &[String]) -> usize {
 }
 
 #[aoc(day7, part2)]
-fn part2(output: &[String]) -> usize {
+fn part2(output: &[String]) -> Option<usize> {
     let filesystem = get_filesystem(output);
-    let min_free_size = filesystem.total_size() - 40000000;
+    let min_free_size = filesystem.total_size().saturating_sub(40000000);
 
     // Filter out all files and directories that have a size lower than the minimum free size.
     let mut candidates = filesystem.get_children_recursive();
 
     candidates.retain(|c| c.node_type == NodeType::Directory && c.size >= min_free_size);
 
-    // Sort the candidates by size and return the size of the smallest one.
-    candidates.sort_by(|a, b| a.size.cmp(&b.size));
-    candidates.first().unwrap().size
+    // Return the size of the smallest candidate, if there are any directories at all.
+    candidates.iter().map(|c| c.size).min()
 }
 
 fn get_filesystem(output: &[String]) -> Node {
@@ -107,12 +153,15 @@ fn get_filesystem(output: &[String]) -> Node {
         children: Vec::new(),
     };
 
+    // The generator checked that every `cd` goes to a listed directory, so the current
+    // directory can always be found.
     let mut path = vec!["/".to_string()];
+    let re = Regex::new(r"(\d+) (.+)").unwrap();
 
     for line in output {
         match line {
-            // Skip navigating to the root directory.
-            l if l.eq("$ cd /") => {}
+            // Navigate to the root directory.
+            l if l.eq("$ cd /") => path.truncate(1),
             // Skip listing the current directory.
             l if l.eq("$ ls") => {}
             // Navigate to the parent directory.
@@ -133,7 +182,6 @@ fn get_filesystem(output: &[String]) -> Node {
             }
             // Add a file if the line starts with the filesize.
             l if l.starts_with(char::is_numeric) => {
-                let re = Regex::new(r"(\d+) (.+)").unwrap();
                 let caps = re.captures(l).unwrap();
                 let size = caps[1].parse::<usize>().unwrap();
                 let name = caps[2].to_string();
@@ -148,9 +196,14 @@ fn get_filesystem(output: &[String]) -> Node {
     filesystem
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(7, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
@@ -181,19 +234,64 @@ mod tests {
             "7214296 k".to_string(),
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("$ cd /\n$ ls\n$ rm -rf /\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(7, 3, 1, "$ rm -rf /", "unrecognized terminal output"),
+            error
+        );
+
+        let input = get_test_input().replace("$ cd d", "$ cd foo");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(7, 18, 6, "$ cd foo", "unknown directory"),
+            error
+        );
+
+        // Directory `e` is only listed in directory `a`.
+        let input = get_test_input().replace("$ cd d", "$ cd e");
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!(
+            ParseError::new(7, 18, 6, "$ cd e", "unknown directory"),
+            error
+        );
+
+        let error = parse_input("$ cd /\n$ cd ..\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(7, 2, 1, "$ cd ..", "cannot leave the root directory"),
+            error
+        );
+    }
+
+    #[test]
+    fn test_solve_invalid() {
+        // Nothing needs to be deleted, but there is no directory to delete either.
+        let input = parse_input("$ cd /\n$ ls\n100 a.txt\n").unwrap();
+        assert_eq!(None, part2(&input));
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(95437, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
-        assert_eq!(24933642, part2(&input));
+        let input = parse_input(get_test_input()).unwrap();
+        assert_eq!(Some(24933642), part2(&input));
     }
 
     fn get_test_input<'a>() -> &'a str {
@@ -1,152 +1,68 @@
+use crate::error::ParseError;
+use crate::geometry::Direction;
+use crate::grid::Grid;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::ops::Range;
-
-#[derive(Debug, Clone, Copy)]
-enum Directions {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Directions {
-    const VALUES: [Self; 4] = [
-        Directions::North,
-        Directions::South,
-        Directions::East,
-        Directions::West,
-    ];
-
-    fn ranges(
-        &self,
-        xpos: usize,
-        ypos: usize,
-        xsize: usize,
-        ysize: usize,
-    ) -> (Range<usize>, Range<usize>) {
-        match self {
-            Directions::North => (xpos..xpos + 1, 0..ypos),
-            Directions::East => (xpos + 1..xsize, ypos..ypos + 1),
-            Directions::South => (xpos..xpos + 1, ypos + 1..ysize),
-            Directions::West => (0..xpos, ypos..ypos + 1),
-        }
-    }
-}
 
 #[derive(Debug, Clone, PartialEq)]
 struct Heightmap {
-    data: Vec<Vec<u8>>,
+    grid: Grid<u8>,
 }
 
 impl Heightmap {
     fn get(&self, x: usize, y: usize) -> u8 {
-        self.data[y][x]
-    }
-
-    fn is_edge(&self, x: usize, y: usize) -> bool {
-        x == 0 || y == 0 || x == self.data[0].len() - 1 || y == self.data.len() - 1
-    }
-
-    fn xsize(&self) -> usize {
-        self.data[0].len()
-    }
-
-    fn ysize(&self) -> usize {
-        self.data.len()
+        self.grid[(x, y)]
     }
 
     fn is_visible(&self, x: usize, y: usize) -> bool {
         // Trees on the edge of the forest are always visible.
-        if self.is_edge(x, y) {
+        if self.grid.is_edge(x, y) {
             return true;
         }
 
         // The tree is visible if all the squares between it and the edge have a lower height.
         let height = self.get(x, y);
 
-        for dir in Directions::VALUES.iter() {
-            let (x_range, y_range) = dir.ranges(x, y, self.xsize(), self.ysize());
-            let mut visible = true;
-
-            'outer: for x2 in x_range {
-                for y2 in y_range.clone() {
-                    if self.get(x2, y2) >= height {
-                        visible = false;
-                        break 'outer;
-                    }
-                }
-            }
-
-            if visible {
-                return true;
-            }
-        }
-
-        false
+        Direction::VALUES.iter().any(|dir| {
+            let delta = dir.delta();
+            self.grid
+                .ray(x, y, delta.x, delta.y)
+                .all(|(x2, y2)| self.get(x2, y2) < height)
+        })
     }
 
     fn scenic_score(&self, x: usize, y: usize) -> usize {
         // Trees on the edge always score 0.
-        if self.is_edge(x, y) {
+        if self.grid.is_edge(x, y) {
             return 0;
         }
 
-        Directions::VALUES
+        let height = self.get(x, y);
+
+        Direction::VALUES
             .iter()
             .map(|dir| {
-                let (x_range, y_range) = dir.ranges(x, y, self.xsize(), self.ysize());
+                // Count the trees up to and including the first one that blocks the view.
+                let delta = dir.delta();
                 let mut distance = 0;
-                match dir {
-                    Directions::North => {
-                        for y2 in y_range.rev() {
-                            distance += 1;
-                            if self.get(x, y2) >= self.get(x, y) {
-                                break;
-                            }
-                        }
-                        distance
-                    }
-                    Directions::East => {
-                        for x2 in x_range {
-                            distance += 1;
-                            if self.get(x2, y) >= self.get(x, y) {
-                                break;
-                            }
-                        }
-                        distance
-                    }
-                    Directions::South => {
-                        for y2 in y_range {
-                            distance += 1;
-                            if self.get(x, y2) >= self.get(x, y) {
-                                break;
-                            }
-                        }
-                        distance
-                    }
-                    Directions::West => {
-                        for x2 in x_range.rev() {
-                            distance += 1;
-                            if self.get(x2, y) >= self.get(x, y) {
-                                break;
-                            }
-                        }
-                        distance
+                for (x2, y2) in self.grid.ray(x, y, delta.x, delta.y) {
+                    distance += 1;
+                    if self.get(x2, y2) >= height {
+                        break;
                     }
                 }
+                distance
             })
             .product()
     }
 }
 
 #[aoc_generator(day8)]
-fn parse_input(input: &str) -> Heightmap {
-    Heightmap {
-        data: input
-            .lines()
-            .map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as u8).collect())
-            .collect(),
-    }
+fn parse_input(input: &str) -> Result<Heightmap, ParseError> {
+    let input = normalise(input);
+    let grid = Grid::parse(8, &input, |c| c.to_digit(10).map(|d| d as u8))?;
+    Ok(Heightmap { grid })
 }
 
 #[aoc(day8, part1)]
@@ -154,11 +70,9 @@ fn part1(heightmap: &Heightmap) -> usize {
     // Count the number of visible trees.
     let mut count = 0;
 
-    for x in 0..heightmap.xsize() {
-        for y in 0..heightmap.ysize() {
-            if heightmap.is_visible(x, y) {
-                count += 1;
-            }
+    for (x, y) in heightmap.grid.positions() {
+        if heightmap.is_visible(x, y) {
+            count += 1;
         }
     }
 
@@ -170,47 +84,71 @@ fn part2(heightmap: &Heightmap) -> usize {
     // Calculate the scenic score for each tree.
     let mut highest_score = 0;
 
-    for x in 0..heightmap.xsize() {
-        for y in 0..heightmap.ysize() {
-            let score = heightmap.scenic_score(x, y);
-            if score > highest_score {
-                highest_score = score;
-            }
+    for (x, y) in heightmap.grid.positions() {
+        let score = heightmap.scenic_score(x, y);
+        if score > highest_score {
+            highest_score = score;
         }
     }
 
     highest_score
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(8, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
     fn test_parse_input() {
+        let data = [
+            [3, 0, 3, 7, 3],
+            [2, 5, 5, 1, 2],
+            [6, 5, 3, 3, 2],
+            [3, 3, 5, 4, 9],
+            [3, 5, 3, 9, 0],
+        ];
         let expected = Heightmap {
-            data: vec![
-                vec![3, 0, 3, 7, 3],
-                vec![2, 5, 5, 1, 2],
-                vec![6, 5, 3, 3, 2],
-                vec![3, 3, 5, 4, 9],
-                vec![3, 5, 3, 9, 0],
-            ],
+            grid: Grid::from_fn(5, 5, |x, y| data[y][x]),
         };
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("303\n2a5\n").unwrap_err();
+        assert_eq!(ParseError::new(8, 2, 2, "2a5", "invalid character"), error);
+
+        let error = parse_input("303\n25\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(8, 2, 1, "25", "inconsistent row length"),
+            error
+        );
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(21, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(8, part2(&input));
     }
 
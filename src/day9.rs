@@ -1,101 +1,53 @@
+use crate::error::ParseError;
+use crate::geometry::{Direction, Point};
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Directions {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(Debug, Clone, PartialEq)]
 struct Motion {
-    direction: Directions,
+    direction: Direction,
     distance: usize,
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Coordinate {
-    x: isize,
-    y: isize,
-}
-
-impl Coordinate {
-    fn step(&mut self, direction: &Directions) {
-        match direction {
-            Directions::Up => self.y -= 1,
-            Directions::Down => self.y += 1,
-            Directions::Left => self.x -= 1,
-            Directions::Right => self.x += 1,
-        }
-    }
-
-    fn follow(&mut self, target: &Coordinate) {
-        let xdelta = target.x - self.x;
-        let ydelta = target.y - self.y;
-
-        // Only follow if the target is more than 1 step away.
-        if xdelta.abs() < 2 && ydelta.abs() < 2 {
-            return;
-        }
-
-        // Move diagonally if possible.
-        if xdelta.abs() > 0 && ydelta.abs() > 0 {
-            if xdelta > 0 {
-                self.step(&Directions::Right);
-            } else {
-                self.step(&Directions::Left);
-            }
-
-            if ydelta > 0 {
-                self.step(&Directions::Down);
-            } else {
-                self.step(&Directions::Up);
-            }
-        } else {
-            // Move either horizontally or vertically.
-            if xdelta.abs() > 1 {
-                if xdelta > 0 {
-                    self.step(&Directions::Right);
-                } else {
-                    self.step(&Directions::Left);
-                }
-            } else if ydelta > 0 {
-                self.step(&Directions::Down);
-            } else {
-                self.step(&Directions::Up);
-            }
-        }
+// Moves the knot one step towards the target, but only if they are no longer touching.
+fn follow(knot: &mut Point<isize>, target: &Point<isize>) {
+    if knot.chebyshev(target) > 1 {
+        *knot += (*target - *knot).signum();
     }
 }
 
 #[aoc_generator(day9)]
-fn parse_input(input: &str) -> Vec<Motion> {
+fn parse_input(input: &str) -> Result<Vec<Motion>, ParseError> {
+    let input = normalise(input);
     input
         .lines()
-        .map(|line| {
-            let (direction, distance) = line.split_at(1);
-            let direction = match direction {
-                "U" => Directions::Up,
-                "D" => Directions::Down,
-                "L" => Directions::Left,
-                "R" => Directions::Right,
-                _ => panic!("Invalid direction"),
-            };
-            let distance = distance[1..].parse().unwrap();
-            Motion {
+        .enumerate()
+        .map(|(i, line)| {
+            let error = |column, reason| ParseError::new(9, i + 1, column, line, reason);
+            let (direction, distance) = line
+                .split_once(' ')
+                .ok_or_else(|| error(1, "expected a direction and a distance"))?;
+            let mut chars = direction.chars();
+            let direction = match (chars.next(), chars.next()) {
+                (Some(c), None) => Direction::from_char(c),
+                _ => None,
+            }
+            .ok_or_else(|| error(1, "invalid direction"))?;
+            let distance = distance.parse().map_err(|_| error(3, "invalid distance"))?;
+            Ok(Motion {
                 direction,
                 distance,
-            }
+            })
         })
         .collect()
 }
 
 #[aoc(day9, part1)]
 fn part1(motions: &[Motion]) -> usize {
-    let mut hcoord = Coordinate { x: 0, y: 0 };
-    let mut tcoord = Coordinate { x: 0, y: 0 };
+    let mut hcoord = Point::new(0, 0);
+    let mut tcoord = Point::new(0, 0);
 
     let mut visited = HashSet::new();
 
@@ -104,8 +56,8 @@ fn part1(motions: &[Motion]) -> usize {
 
     for motion in motions {
         for _ in 0..motion.distance {
-            hcoord.step(&motion.direction);
-            tcoord.follow(&hcoord);
+            hcoord += motion.direction.delta();
+            follow(&mut tcoord, &hcoord);
             visited.insert(tcoord);
         }
     }
@@ -115,7 +67,7 @@ fn part1(motions: &[Motion]) -> usize {
 
 #[aoc(day9, part2)]
 fn part2(motions: &[Motion]) -> usize {
-    let mut coords: [Coordinate; 10] = [Coordinate { x: 0, y: 0 }; 10];
+    let mut coords: [Point<isize>; 10] = [Point::new(0, 0); 10];
 
     let mut visited = HashSet::new();
 
@@ -124,10 +76,10 @@ fn part2(motions: &[Motion]) -> usize {
 
     for motion in motions {
         for _ in 0..motion.distance {
-            coords[0].step(&motion.direction);
+            coords[0] += motion.direction.delta();
             for i in 1..10 {
                 let previous_coord = coords[i - 1];
-                coords[i].follow(&previous_coord);
+                follow(&mut coords[i], &previous_coord);
             }
             visited.insert(coords[9]);
         }
@@ -136,60 +88,88 @@ fn part2(motions: &[Motion]) -> usize {
     visited.len()
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(9, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
     fn test_parse_input() {
         let expected = vec![
             Motion {
-                direction: Directions::Right,
+                direction: Direction::East,
                 distance: 4,
             },
             Motion {
-                direction: Directions::Up,
+                direction: Direction::North,
                 distance: 4,
             },
             Motion {
-                direction: Directions::Left,
+                direction: Direction::West,
                 distance: 3,
             },
             Motion {
-                direction: Directions::Down,
+                direction: Direction::South,
                 distance: 1,
             },
             Motion {
-                direction: Directions::Right,
+                direction: Direction::East,
                 distance: 4,
             },
             Motion {
-                direction: Directions::Down,
+                direction: Direction::South,
                 distance: 1,
             },
             Motion {
-                direction: Directions::Left,
+                direction: Direction::West,
                 distance: 5,
             },
             Motion {
-                direction: Directions::Right,
+                direction: Direction::East,
                 distance: 2,
             },
         ];
 
-        assert_eq!(expected, parse_input(get_test_input()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
+    }
+
+    #[test]
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
+    }
+
+    #[test]
+    fn test_parse_input_invalid() {
+        let error = parse_input("R 4\nX 4\n").unwrap_err();
+        assert_eq!(ParseError::new(9, 2, 1, "X 4", "invalid direction"), error);
+
+        let error = parse_input("U -1\n").unwrap_err();
+        assert_eq!(ParseError::new(9, 1, 3, "U -1", "invalid distance"), error);
+
+        let error = parse_input("L3\n").unwrap_err();
+        assert_eq!(
+            ParseError::new(9, 1, 1, "L3", "expected a direction and a distance"),
+            error
+        );
     }
 
     #[test]
     fn part1_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(13, part1(&input));
     }
 
     #[test]
     fn part2_example() {
-        let input = parse_input(get_test_input());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(1, part2(&input));
     }
 
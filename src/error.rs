@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error that occurred while parsing the puzzle input.
+///
+/// Line and column numbers are 1-based, so they can be matched directly against the input file
+/// in an editor.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, text: &str, reason: &str) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {} in {:?}",
+            self.day, self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+// The aoc runner prints errors using the pretty debug format. Print the human readable message
+// instead of the struct fields so the diagnostic is actually useful.
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParseError::new(4, 3, 1, "2-4;6-8", "invalid assignment pair");
+        assert_eq!(
+            "day 4, line 3, column 1: invalid assignment pair in \"2-4;6-8\"",
+            error.to_string()
+        );
+        assert_eq!(error.to_string(), format!("{:#?}", error));
+    }
+}
@@ -0,0 +1,383 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point, or vector, in two dimensional space.
+///
+/// The `y` axis points down, as is common when working with puzzle input laid out in rows, so
+/// going north decreases `y`. Use `Point<isize>` for positions that can move freely and
+/// `Point<usize>` for positions that index into a grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+// Distances are implemented for the concrete coordinate types, since the generic versions would
+// need a numeric trait which the standard library does not provide.
+macro_rules! impl_distance {
+    ($($t:ty),*) => {
+        $(
+            impl Point<$t> {
+                /// Returns the taxicab distance, i.e. the number of orthogonal steps.
+                pub fn manhattan(&self, other: &Self) -> usize {
+                    self.x.abs_diff(other.x) as usize + self.y.abs_diff(other.y) as usize
+                }
+
+                /// Returns the chessboard distance, i.e. the number of king moves.
+                pub fn chebyshev(&self, other: &Self) -> usize {
+                    self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)) as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_distance!(isize, usize, i32, i64);
+
+impl Point<isize> {
+    /// Returns a vector with each component reduced to -1, 0 or 1.
+    pub fn signum(&self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Returns the horizontally and vertically adjacent points.
+    pub fn neighbours4(&self) -> impl Iterator<Item = Self> + '_ {
+        Direction::VALUES.iter().map(move |d| *self + d.delta())
+    }
+
+    /// Returns the horizontally, vertically and diagonally adjacent points.
+    pub fn neighbours8(&self) -> impl Iterator<Item = Self> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Self::new(dx, dy)))
+            .filter(|delta| *delta != Self::default())
+            .map(move |delta| *self + delta)
+    }
+
+    /// Converts the point into an unsigned point, if both coordinates are non-negative.
+    pub fn to_unsigned(self) -> Option<Point<usize>> {
+        Some(Point::new(
+            usize::try_from(self.x).ok()?,
+            usize::try_from(self.y).ok()?,
+        ))
+    }
+}
+
+impl Point<usize> {
+    /// Converts the point into a signed point.
+    pub fn to_signed(self) -> Point<isize> {
+        Point::new(self.x as isize, self.y as isize)
+    }
+
+    /// Returns the point as an `(x, y)` tuple, as used to index a `Grid`.
+    pub fn to_tuple(self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    /// Converts an `(x, y)` tuple into a point.
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// A point, or vector, in three dimensional space.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy> Point3<T> {
+    /// Returns the dot product, i.e. the sum of the products of the coordinates.
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
+
+impl<T: Ord + Copy> Point3<T> {
+    /// Returns the point with the smallest coordinate of both points along every axis.
+    pub fn component_min(&self, other: &Self) -> Self {
+        Self::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Returns the point with the largest coordinate of both points along every axis.
+    pub fn component_max(&self, other: &Self) -> Self {
+        Self::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Returns the smallest and the largest corner of the axis-aligned box that contains all the
+    /// given points, or `None` if there are no points.
+    pub fn bounding_box<I>(points: I) -> Option<(Self, Self)>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        points.into_iter().fold(None, |bounds, p| match bounds {
+            None => Some((p, p)),
+            Some((min, max)) => Some((min.component_min(&p), max.component_max(&p))),
+        })
+    }
+
+    /// Returns whether the point lies within the box between the given corners, inclusive.
+    pub fn is_within(&self, min: &Self, max: &Self) -> bool {
+        (min.x..=max.x).contains(&self.x)
+            && (min.y..=max.y).contains(&self.y)
+            && (min.z..=max.z).contains(&self.z)
+    }
+}
+
+macro_rules! impl_distance3 {
+    ($($t:ty),*) => {
+        $(
+            impl Point3<$t> {
+                /// Returns the taxicab distance, i.e. the number of orthogonal steps.
+                pub fn manhattan(&self, other: &Self) -> usize {
+                    self.x.abs_diff(other.x) as usize
+                        + self.y.abs_diff(other.y) as usize
+                        + self.z.abs_diff(other.z) as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_distance3!(isize, usize, i32, i64);
+
+impl Point3<isize> {
+    /// The unit vectors along the positive and negative axes.
+    pub const DIRECTIONS: [Self; 6] = [
+        Self::new(1, 0, 0),
+        Self::new(-1, 0, 0),
+        Self::new(0, 1, 0),
+        Self::new(0, -1, 0),
+        Self::new(0, 0, 1),
+        Self::new(0, 0, -1),
+    ];
+
+    /// Returns the six points that share a face with this point.
+    pub fn neighbours6(&self) -> impl Iterator<Item = Self> + '_ {
+        Self::DIRECTIONS.iter().map(move |&d| *self + d)
+    }
+}
+
+/// One of the four orthogonal directions, listed in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const VALUES: [Self; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Parses a direction from either a compass letter (`N`, `E`, `S`, `W`) or a relative
+    /// letter (`U`, `R`, `D`, `L`).
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'N' | 'U' => Some(Direction::North),
+            'E' | 'R' => Some(Direction::East),
+            'S' | 'D' => Some(Direction::South),
+            'W' | 'L' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// Returns the unit vector pointing in this direction.
+    pub fn delta(&self) -> Point<isize> {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, 1),
+            Direction::West => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        Self::VALUES[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Self {
+        Self::VALUES[(*self as usize + 3) % 4]
+    }
+
+    pub fn reverse(&self) -> Self {
+        Self::VALUES[(*self as usize + 2) % 4]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(-1, 4);
+        assert_eq!(Point::new(2, 2), a + b);
+        assert_eq!(Point::new(4, -6), a - b);
+        assert_eq!(Point::new(9, -6), a * 3);
+        assert_eq!(Point::new(-3, 2), -a);
+        assert_eq!(Point::new(1, -1), a.signum());
+        assert_eq!(10, a.manhattan(&b));
+        assert_eq!(6, a.chebyshev(&b));
+        assert_eq!(None, a.to_unsigned());
+        assert_eq!(Some(Point::new(3, 2)), Point::new(3, 2).to_unsigned());
+        assert_eq!(4, Point::new(1usize, 5).manhattan(&Point::new(3, 3)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(0, 0);
+        assert_eq!(
+            vec![
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ],
+            p.neighbours4().collect::<Vec<_>>()
+        );
+        assert_eq!(8, p.neighbours8().count());
+        assert!(p.neighbours8().all(|n| n.chebyshev(&p) == 1));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Some(Direction::North), Direction::from_char('U'));
+        assert_eq!(Some(Direction::West), Direction::from_char('W'));
+        assert_eq!(None, Direction::from_char('X'));
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!(Direction::West, Direction::North.turn_left());
+        assert_eq!(Direction::South, Direction::North.reverse());
+        assert_eq!(Point::new(-1, 0), Direction::West.delta());
+    }
+
+    #[test]
+    fn test_point3() {
+        let a = Point3::new(1isize, -2, 3);
+        let b = Point3::new(-1, 4, 0);
+        assert_eq!(Point3::new(0, 2, 3), a + b);
+        assert_eq!(Point3::new(2, -6, 3), a - b);
+        assert_eq!(Point3::new(2, -4, 6), a * 2);
+        assert_eq!(Point3::new(-1, 2, -3), -a);
+        assert_eq!(-9, a.dot(&b));
+        assert_eq!(11, a.manhattan(&b));
+        assert_eq!(6, a.neighbours6().count());
+        assert!(a.neighbours6().all(|n| n.manhattan(&a) == 1));
+
+        let bounds = Point3::bounding_box([a, b, Point3::new(0, 5, -1)]);
+        assert_eq!(
+            Some((Point3::new(-1, -2, -1), Point3::new(1, 5, 3))),
+            bounds
+        );
+        assert_eq!(None, Point3::<isize>::bounding_box([]));
+        let (min, max) = bounds.unwrap();
+        assert!(a.is_within(&min, &max));
+        assert!(!Point3::new(2, 0, 0).is_within(&min, &max));
+    }
+}
@@ -0,0 +1,324 @@
+use crate::error::ParseError;
+use std::ops::{Index, IndexMut};
+
+/// A rectangular two dimensional grid of cells.
+///
+/// Cells are addressed with `(x, y)` tuples, where `x` is the column and `y` is the row. The
+/// origin is in the top left corner, so `y` increases going down.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid by calling the given function for every position.
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let mut cells = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(f(x, y));
+            }
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a grid from text, with one row per line and one cell per character.
+    ///
+    /// The `parse_cell` function converts a character into a cell, returning `None` if the
+    /// character is not valid. Errors are reported for the given puzzle day.
+    pub fn parse<F>(day: u8, input: &str, parse_cell: F) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for (i, line) in input.lines().enumerate() {
+            let mut row_width = 0;
+            for (j, c) in line.chars().enumerate() {
+                let cell = parse_cell(c)
+                    .ok_or_else(|| ParseError::new(day, i + 1, j + 1, line, "invalid character"))?;
+                cells.push(cell);
+                row_width += 1;
+            }
+            if i == 0 {
+                width = row_width;
+            }
+            if row_width == 0 || row_width != width {
+                return Err(ParseError::new(
+                    day,
+                    i + 1,
+                    1,
+                    line,
+                    "inconsistent row length",
+                ));
+            }
+            height += 1;
+        }
+
+        if height == 0 {
+            return Err(ParseError::new(day, 1, 1, "", "empty grid"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the given signed position lies within the grid.
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn is_edge(&self, x: usize, y: usize) -> bool {
+        x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Returns all positions in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Returns the position of the first cell, in reading order, matching the predicate.
+    pub fn position<P>(&self, predicate: P) -> Option<(usize, usize)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| (i % self.width, i / self.width))
+    }
+
+    /// Returns the cells of the given row from left to right, or `None` if the row lies outside
+    /// the grid.
+    pub fn row(&self, y: usize) -> Option<impl DoubleEndedIterator<Item = &T>> {
+        if y < self.height {
+            Some(self.cells[y * self.width..(y + 1) * self.width].iter())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cells of the given column from top to bottom, or `None` if the column lies
+    /// outside the grid.
+    pub fn column(&self, x: usize) -> Option<impl DoubleEndedIterator<Item = &T>> {
+        if x < self.width {
+            Some(self.cells[x..].iter().step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the positions encountered when walking from the given position in a straight
+    /// line until the edge of the grid. The starting position itself is not included.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (1..)
+            .map(move |i| (x as isize + dx * i, y as isize + dy * i))
+            .take_while(move |&(x, y)| (dx, dy) != (0, 0) && self.contains(x, y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    /// Returns the horizontally and vertically adjacent positions that lie within the grid.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(x, y, &[(0, -1), (1, 0), (0, 1), (-1, 0)])
+    }
+
+    /// Returns the horizontally, vertically and diagonally adjacent positions that lie within
+    /// the grid.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(
+            x,
+            y,
+            &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+            ],
+        )
+    }
+
+    fn neighbours(
+        &self,
+        x: usize,
+        y: usize,
+        deltas: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        deltas
+            .iter()
+            .map(move |(dx, dy)| (x as isize + dx, y as isize + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    /// Renders the grid as text, with one line per row.
+    pub fn render<F>(&self, render_cell: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        (0..self.height)
+            .map(|y| {
+                self.cells[y * self.width..(y + 1) * self.width]
+                    .iter()
+                    .map(&render_cell)
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Returns a copy of the grid with the rows and columns swapped.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("position out of bounds")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y).expect("position out of bounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse() {
+        let grid = get_test_grid();
+        assert_eq!(3, grid.width());
+        assert_eq!(2, grid.height());
+        assert_eq!(Some(&1), grid.get(1, 0));
+        assert_eq!(Some(&5), grid.get(2, 1));
+        assert_eq!(None, grid.get(3, 0));
+        assert_eq!(None, grid.get(0, 2));
+
+        let error = Grid::parse(0, "12\n1x\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(ParseError::new(0, 2, 2, "1x", "invalid character"), error);
+
+        let error = Grid::parse(0, "12\n123\n", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            ParseError::new(0, 2, 1, "123", "inconsistent row length"),
+            error
+        );
+
+        let error = Grid::parse(0, "", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(ParseError::new(0, 1, 1, "", "empty grid"), error);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = get_test_grid();
+        assert_eq!(vec![&3, &4, &5], grid.row(1).unwrap().collect::<Vec<_>>());
+        assert_eq!(vec![&2, &5], grid.column(2).unwrap().collect::<Vec<_>>());
+        assert!(grid.row(2).is_none());
+        assert!(grid.column(3).is_none());
+        assert_eq!(
+            vec![(1, 0), (2, 0)],
+            grid.ray(0, 0, 1, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![(1, 0)], grid.ray(2, 1, -1, -1).collect::<Vec<_>>());
+        assert_eq!(
+            vec![(1, 0), (2, 1), (0, 1)],
+            grid.neighbours4(1, 1).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, 0), (1, 1), (0, 1)],
+            grid.neighbours8(0, 0).collect::<Vec<_>>()
+        );
+        assert_eq!(Some((1, 1)), grid.position(|&c| c == 4));
+    }
+
+    #[test]
+    fn test_transpose_and_render() {
+        let grid = get_test_grid().transpose();
+        assert_eq!(2, grid.width());
+        assert_eq!(3, grid.height());
+        let expected = indoc! {"
+            03
+            14
+            25"};
+        assert_eq!(expected, grid.render(|&c| char::from_digit(c, 10).unwrap()));
+
+        // A grid without columns still has its rows.
+        let empty = Grid::new(0, 2, 0);
+        assert_eq!("\n", empty.render(|&c| char::from_digit(c, 10).unwrap()));
+        assert_eq!(0, empty.row(1).unwrap().count());
+        assert!(empty.column(0).is_none());
+    }
+
+    fn get_test_grid() -> Grid<u32> {
+        let input = indoc! {"
+            012
+            345
+        "};
+        Grid::parse(0, input, |c| c.to_digit(10)).unwrap()
+    }
+}
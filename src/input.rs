@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+
+/// Normalises raw puzzle input before it is parsed.
+///
+/// - A leading UTF-8 byte order mark is removed.
+/// - Windows line endings (CRLF) are converted to LF.
+/// - Trailing whitespace at the end of the input is removed, including the final newline.
+///   Whitespace at the start or end of the other lines is significant in some puzzles, so it is
+///   left untouched.
+pub fn normalise(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Splits normalised input into paragraphs separated by one or more blank lines.
+///
+/// Every paragraph is returned together with the 0-based index of its first line in the input,
+/// so parse errors can report the correct line number.
+pub fn paragraphs(input: &str) -> Vec<(usize, &str)> {
+    let mut paragraphs = vec![];
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split('\n').enumerate() {
+        let end = offset + line.len();
+        match (line.trim().is_empty(), start) {
+            (false, None) => start = Some((i, offset)),
+            (true, Some((first_line, first_offset))) => {
+                // Exclude the newline that precedes the blank line.
+                paragraphs.push((first_line, &input[first_offset..offset - 1]));
+                start = None;
+            }
+            _ => {}
+        }
+        offset = end + 1;
+    }
+    if let Some((first_line, first_offset)) = start {
+        paragraphs.push((first_line, &input[first_offset..]));
+    }
+
+    paragraphs
+}
+
+/// Returns variants of the given input with different line endings, a byte order mark and
+/// missing or extra trailing newlines. Parsing any of them should give the same result as
+/// parsing the original.
+#[cfg(test)]
+pub fn variants(input: &str) -> Vec<String> {
+    let trimmed = input.trim_end_matches('\n');
+    vec![
+        input.replace('\n', "\r\n"),
+        trimmed.to_string(),
+        trimmed.replace('\n', "\r\n"),
+        format!("{}\n\n", trimmed),
+        format!("\u{feff}{}", input),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!("a\nb", normalise("a\nb\n"));
+        assert_eq!("a\nb", normalise("\u{feff}a\r\nb\r\n\r\n"));
+        assert_eq!("  a \n b", normalise("  a \r\n b  \n"));
+        assert!(matches!(normalise("a\nb\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert_eq!(
+            vec![(0, "a\nb"), (3, "c"), (7, " d\ne")],
+            paragraphs("a\nb\n\nc\n\n \n\n d\ne")
+        );
+        assert_eq!(vec![(1, "a")], paragraphs("\na\n\n"));
+        assert!(paragraphs("").is_empty());
+    }
+}
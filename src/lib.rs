@@ -1,9 +1,25 @@
 use aoc_runner_derive::aoc_lib;
+use solver::{DaySolver, Solver};
+use std::sync::OnceLock;
 
 mod day1;
 mod day10;
+mod day11;
 mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
 mod day2;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 mod day3;
 mod day4;
 mod day5;
@@ -11,5 +27,57 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+
+pub use day1::{calorie_report, ElfTotal};
+
+/// Returns the solvers for all implemented days, ordered by day.
+pub fn solvers() -> impl Iterator<Item = &'static dyn Solver> {
+    static SOLVERS: OnceLock<Vec<DaySolver>> = OnceLock::new();
+    SOLVERS
+        .get_or_init(|| {
+            vec![
+                day1::solver(),
+                day2::solver(),
+                day3::solver(),
+                day4::solver(),
+                day5::solver(),
+                day6::solver(),
+                day7::solver(),
+                day8::solver(),
+                day9::solver(),
+                day10::solver(),
+                day11::solver(),
+                day12::solver(),
+                day13::solver(),
+                day14::solver(),
+                day15::solver(),
+                day16::solver(),
+                day17::solver(),
+                day18::solver(),
+                day19::solver(),
+                day20::solver(),
+                day21::solver(),
+                day22::solver(),
+                day23::solver(),
+                day24::solver(),
+                day25::solver(),
+            ]
+        })
+        .iter()
+        .map(|solver| solver as &dyn Solver)
+}
+
+/// Returns the solver for the given day, if it has been implemented.
+pub fn find_solver(day: u8) -> Option<&'static dyn Solver> {
+    solvers().find(|solver| solver.day() == day)
+}
 
 aoc_lib! { year = 2022 }
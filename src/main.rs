@@ -1,3 +1,208 @@
-use aoc_runner_derive::aoc_main;
+use advent_of_code_2022::runner::{self, Report};
+use advent_of_code_2022::scaffold;
+use advent_of_code_2022::solver::{Answer, Part, Solver};
+use advent_of_code_2022::{find_solver, solvers};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+use std::{env, fs, io};
 
-aoc_main! { lib = advent_of_code_2022 }
+const USAGE: &str = "\
+Usage:
+  advent_of_code_2022 run (--day <DAY> | --all) [--part <PART>] [--input <PATH>] [--json]
+  advent_of_code_2022 new-day <DAY>
+
+Commands:
+  run        Solve puzzles and report the answers with the parse and solve times.
+  new-day    Generate the module for a new day from the template. Run this from the
+             project root.
+
+Options:
+  --day <DAY>      The day to solve.
+  --all            Solve all days for which the puzzle input is available.
+  --part <PART>    Only solve the given part, 1 or 2. Both parts are solved by default.
+  --input <PATH>   Read the puzzle input from this file, or from stdin if the path is `-`.
+                   Defaults to `input/2022/day<DAY>.txt`. Cannot be combined with --all.
+  --json           Output the results as JSON.";
+
+#[derive(Debug, Default)]
+struct RunOptions {
+    day: Option<u8>,
+    all: bool,
+    part: Option<Part>,
+    input: Option<String>,
+    json: bool,
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("run") => parse_run_options(&args[1..]).and_then(|options| run(&options)),
+        Some("new-day") => new_day(&args[1..]).map(|_| true),
+        None | Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Some(command) => Err(format!("unknown command {:?}", command)),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        // Some of the puzzles could not be solved. The errors have already been reported.
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn parse_run_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--day" => {
+                let day = value()?;
+                options.day = Some(day.parse().map_err(|_| format!("invalid day {:?}", day))?);
+            }
+            "--part" => {
+                let part = value()?;
+                let number = part
+                    .parse::<u8>()
+                    .map_err(|_| format!("invalid part {:?}", part))?;
+                options.part = Some(Part::try_from(number)?);
+            }
+            "--input" => options.input = Some(value()?.to_string()),
+            "--all" => options.all = true,
+            "--json" => options.json = true,
+            _ => return Err(format!("unknown option {:?}", arg)),
+        }
+    }
+
+    match options {
+        RunOptions {
+            day: None,
+            all: false,
+            ..
+        } => Err("either --day or --all is required".to_string()),
+        RunOptions {
+            day: Some(_),
+            all: true,
+            ..
+        } => Err("--day cannot be combined with --all".to_string()),
+        RunOptions {
+            input: Some(_),
+            all: true,
+            ..
+        } => Err("--input cannot be combined with --all".to_string()),
+        options => Ok(options),
+    }
+}
+
+// Solves the selected puzzles and outputs the reports. Returns whether all of them succeeded.
+fn run(options: &RunOptions) -> Result<bool, String> {
+    let mut puzzles: Vec<(&dyn Solver, String)> = vec![];
+
+    if let Some(day) = options.day {
+        let solver = find_solver(day).ok_or_else(|| format!("day {} is not implemented", day))?;
+        let input = match options.input.as_deref() {
+            Some("-") => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("cannot read from stdin: {}", e))?;
+                input
+            }
+            Some(path) => {
+                fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?
+            }
+            None => {
+                let path = runner::input_path(day);
+                fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+            }
+        };
+        puzzles.push((solver, input));
+    } else {
+        for solver in solvers() {
+            let path = runner::input_path(solver.day());
+            match fs::read_to_string(&path) {
+                Ok(input) => puzzles.push((solver, input)),
+                Err(_) => eprintln!(
+                    "Skipping day {}: {} not found.",
+                    solver.day(),
+                    path.display()
+                ),
+            }
+        }
+    }
+
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => Part::VALUES.to_vec(),
+    };
+
+    let mut reports = vec![];
+    for (solver, input) in puzzles {
+        for part in parts.iter() {
+            let report = runner::run(solver, *part, &input);
+            if !options.json {
+                print_report(&report);
+            }
+            reports.push(report);
+        }
+    }
+
+    if options.json {
+        let json = Value::Array(reports.iter().map(report_to_json).collect());
+        println!("{}", serde_json::to_string_pretty(&json).unwrap());
+    }
+
+    Ok(reports.iter().all(|report| report.result.is_ok()))
+}
+
+fn new_day(args: &[String]) -> Result<(), String> {
+    let day = match args {
+        [day] => day.parse().map_err(|_| format!("invalid day {:?}", day))?,
+        _ => return Err("new-day expects a single day".to_string()),
+    };
+    scaffold::new_day(Path::new("."), day)?;
+    println!(
+        "Created src/day{0}.rs and registered day {0} in src/lib.rs.",
+        day
+    );
+    Ok(())
+}
+
+fn print_report(report: &Report) {
+    match &report.result {
+        Ok(answer) => println!(
+            "Day {} - Part {}: {}\n\tparse: {:?}\n\tsolve: {:?}\n",
+            report.day, report.part, answer, report.parse_time, report.solve_time
+        ),
+        Err(e) => eprintln!("Day {} - Part {}: FAILED: {}\n", report.day, report.part, e),
+    }
+}
+
+fn report_to_json(report: &Report) -> Value {
+    let mut json = json!({
+        "day": report.day,
+        "part": report.part.number(),
+        "parse_ns": report.parse_time.as_nanos() as u64,
+        "solve_ns": report.solve_time.as_nanos() as u64,
+    });
+    match &report.result {
+        Ok(Answer::Integer(n)) => json["answer"] = json!(n),
+        Ok(Answer::Text(s)) => json["answer"] = json!(s),
+        Err(e) => json["error"] = json!(e.to_string()),
+    }
+    json
+}
@@ -0,0 +1,157 @@
+use crate::grid::Grid;
+use std::error::Error;
+use std::fmt;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+// Glyphs are separated by a single empty column.
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+// The letters of the font used in the Advent of Code puzzles that render text on a display.
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 16] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// The image does not consist of a single row of glyphs.
+    InvalidSize { width: usize, height: usize },
+    /// The glyphs at the given 0-based positions do not match any known letter.
+    UnrecognisedGlyphs(Vec<usize>),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::InvalidSize { width, height } => write!(
+                f,
+                "invalid image size {}x{}, expected a single row of {}x{} glyphs",
+                width, height, GLYPH_WIDTH, GLYPH_HEIGHT
+            ),
+            OcrError::UnrecognisedGlyphs(positions) => write!(
+                f,
+                "unrecognised glyphs at positions {}",
+                positions
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Reads the text from an image in which lit pixels are `true`.
+pub fn recognise(image: &Grid<bool>) -> Result<String, OcrError> {
+    let (width, height) = (image.width(), image.height());
+    // The spacing after the last glyph is optional.
+    if height != GLYPH_HEIGHT || width == 0 || (width + 1) % GLYPH_SPACING > 1 {
+        return Err(OcrError::InvalidSize { width, height });
+    }
+
+    let mut text = String::new();
+    let mut unrecognised = vec![];
+
+    for i in 0..(width + 1) / GLYPH_SPACING {
+        let offset = i * GLYPH_SPACING;
+        let letter = GLYPHS.iter().find(|(_, rows)| {
+            rows.iter().enumerate().all(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .all(|(x, c)| image[(offset + x, y)] == (c == '#'))
+            })
+        });
+        match letter {
+            Some((c, _)) => text.push(*c),
+            None => unrecognised.push(i),
+        }
+    }
+
+    if unrecognised.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnrecognisedGlyphs(unrecognised))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_recognise() {
+        let image = parse_image(indoc! {"
+            ###...##..###..#..#.###..####..##..###..
+            #..#.#..#.#..#.#..#.#..#.#....#..#.#..#.
+            #..#.#....#..#.####.###..###..#..#.###..
+            ###..#.##.###..#..#.#..#.#....####.#..#.
+            #....#..#.#....#..#.#..#.#....#..#.#..#.
+            #.....###.#....#..#.###..####.#..#.###..
+        "});
+        assert_eq!(Ok("PGPHBEAB".to_string()), recognise(&image));
+    }
+
+    #[test]
+    fn test_recognise_all_glyphs() {
+        for (c, rows) in GLYPHS.iter() {
+            let image = parse_image(&rows.join("\n"));
+            assert_eq!(Ok(c.to_string()), recognise(&image));
+        }
+    }
+
+    #[test]
+    fn test_recognise_invalid() {
+        let image = parse_image(indoc! {"
+            .##..####.###.
+            #..#.#....#..#
+            #..#.###..#..#
+            ####.#....###.
+            #..#.#....#...
+            #..#.###..#...
+        "});
+        assert_eq!(
+            Err(OcrError::UnrecognisedGlyphs(vec![1])),
+            recognise(&image)
+        );
+        assert_eq!(
+            "unrecognised glyphs at positions 1",
+            recognise(&image).unwrap_err().to_string()
+        );
+
+        let image = parse_image("#..#.\n");
+        assert_eq!(
+            Err(OcrError::InvalidSize {
+                width: 5,
+                height: 1
+            }),
+            recognise(&image)
+        );
+    }
+
+    fn parse_image(input: &str) -> Grid<bool> {
+        Grid::parse(0, input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+}
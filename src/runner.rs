@@ -0,0 +1,71 @@
+use crate::solver::{Answer, Part, Solver};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// The outcome of running one part of a puzzle, with the time spent in each phase.
+#[derive(Debug)]
+pub struct Report {
+    pub day: u8,
+    pub part: Part,
+    pub result: Result<Answer, Box<dyn Error>>,
+    pub parse_time: Duration,
+    /// The time spent solving. This is zero if the input could not be parsed.
+    pub solve_time: Duration,
+}
+
+/// Parses the input and solves the given part, timing both phases separately.
+pub fn run(solver: &dyn Solver, part: Part, input: &str) -> Report {
+    let start = Instant::now();
+    let parsed = solver.parse(part, input);
+    let parse_time = start.elapsed();
+
+    let (result, solve_time) = match parsed {
+        Ok(parsed) => {
+            let start = Instant::now();
+            let result = solver.solve(part, &parsed);
+            (result, start.elapsed())
+        }
+        Err(e) => (Err(e.into()), Duration::ZERO),
+    };
+
+    Report {
+        day: solver.day(),
+        part,
+        result,
+        parse_time,
+        solve_time,
+    }
+}
+
+/// Returns the path of the puzzle input for the given day, relative to the project root.
+///
+/// This is the location where `cargo aoc input` stores the downloaded input.
+pub fn input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("input/2022/day{}.txt", day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_solver;
+
+    #[test]
+    fn test_run() {
+        let report = run(
+            find_solver(6).unwrap(),
+            Part::Two,
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+        );
+        assert_eq!(6, report.day);
+        assert_eq!(Part::Two, report.part);
+        assert_eq!(Answer::Integer(19), report.result.unwrap());
+
+        let report = run(find_solver(6).unwrap(), Part::One, "mjqjpq1");
+        assert_eq!(
+            "day 6, line 1, column 7: invalid character in \"mjqjpq1\"",
+            report.result.unwrap_err().to_string()
+        );
+        assert_eq!(Duration::ZERO, report.solve_time);
+    }
+}
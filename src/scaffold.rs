@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::Path;
+
+const TEMPLATE: &str = include_str!("template.rs");
+
+/// Generates the module for a new day from the template and registers it in `src/lib.rs`.
+///
+/// The paths are relative to the given project root. Existing days are never overwritten.
+pub fn new_day(root: &Path, day: u8) -> Result<(), String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("invalid day {}, expected 1 to 25", day));
+    }
+
+    let module_path = root.join(format!("src/day{}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let lib = fs::read_to_string(&lib_path)
+        .map_err(|e| format!("cannot read {}: {}", lib_path.display(), e))?;
+    let lib = register(&lib, day)?;
+
+    fs::write(&module_path, render(day))
+        .map_err(|e| format!("cannot write {}: {}", module_path.display(), e))?;
+    fs::write(&lib_path, lib).map_err(|e| format!("cannot write {}: {}", lib_path.display(), e))
+}
+
+/// Returns the source code of the module for the given day.
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("day0", &format!("day{}", day))
+        .replace("DaySolver::new(0,", &format!("DaySolver::new({},", day))
+}
+
+/// Adds the module declaration and the solver for the given day to the source of `lib.rs`.
+pub fn register(lib: &str, day: u8) -> Result<String, String> {
+    let module = format!("mod day{};", day);
+    let solver = format!("day{}::solver(),", day);
+    if lib.lines().any(|line| line.trim() == module) {
+        return Err(format!("day {} is already registered in lib.rs", day));
+    }
+
+    let mut lines = lib.lines().map(String::from).collect::<Vec<String>>();
+
+    // The module declarations are sorted alphabetically, like rustfmt does.
+    let modules = find_block(&lines, |line| {
+        line.starts_with("mod day") && line.ends_with(';')
+    })
+    .ok_or("cannot find the module declarations in lib.rs")?;
+    lines.insert(modules.end, module);
+    lines[modules.start..=modules.end].sort_by_key(|line| line.trim_end_matches(';').to_string());
+
+    // The solvers are sorted by day.
+    let solvers = find_block(&lines, |line| {
+        line.trim_start().starts_with("day") && line.ends_with("::solver(),")
+    })
+    .ok_or("cannot find the list of solvers in lib.rs")?;
+    let indent = lines[solvers.start].replace(lines[solvers.start].trim_start(), "");
+    lines.insert(solvers.end, format!("{}{}", indent, solver));
+    lines[solvers.start..=solvers.end].sort_by_key(|line| get_day(line));
+
+    Ok(lines.join("\n") + "\n")
+}
+
+// Returns the range of the first consecutive block of lines matching the predicate.
+fn find_block<P>(lines: &[String], predicate: P) -> Option<std::ops::Range<usize>>
+where
+    P: Fn(&str) -> bool,
+{
+    let start = lines.iter().position(|line| predicate(line))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .take_while(|line| predicate(line))
+            .count();
+    Some(start..end)
+}
+
+fn get_day(line: &str) -> u8 {
+    line.trim_start()
+        .trim_start_matches("day")
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_render() {
+        let source = render(11);
+        assert!(!source.contains("day0"));
+        assert!(source.contains("#[aoc_generator(day11)]"));
+        assert!(source.contains("DaySolver::new(11, parse_input"));
+        assert!(!source.contains("todo!"));
+        assert!(source.contains("fn get_test_input<'a>() -> &'a str {\n        indoc! {"));
+    }
+
+    #[test]
+    fn test_register() {
+        let lib = indoc! {"
+            use aoc_runner_derive::aoc_lib;
+
+            mod day1;
+            mod day10;
+            mod day12;
+            mod day2;
+            pub mod error;
+
+            pub fn solvers() {
+                vec![
+                    day1::solver(),
+                    day2::solver(),
+                    day10::solver(),
+                    day12::solver(),
+                ]
+            }
+        "};
+        let expected = indoc! {"
+            use aoc_runner_derive::aoc_lib;
+
+            mod day1;
+            mod day10;
+            mod day11;
+            mod day12;
+            mod day2;
+            pub mod error;
+
+            pub fn solvers() {
+                vec![
+                    day1::solver(),
+                    day2::solver(),
+                    day10::solver(),
+                    day11::solver(),
+                    day12::solver(),
+                ]
+            }
+        "};
+        assert_eq!(Ok(expected.to_string()), register(lib, 11));
+        assert_eq!(
+            Err("day 12 is already registered in lib.rs".to_string()),
+            register(lib, 12)
+        );
+    }
+}
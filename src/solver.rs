@@ -0,0 +1,276 @@
+use crate::error::ParseError;
+use std::any::Any;
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt;
+
+/// One of the two parts of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const VALUES: [Self; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = String;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        match number {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(format!("invalid part {}", number)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Converts the return value of a solution function into an answer.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, Box<dyn Error>>;
+}
+
+macro_rules! impl_into_answer_for_integer {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Result<Answer, Box<dyn Error>> {
+                    Ok(Answer::Integer(i64::try_from(self)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer_for_integer!(usize, isize, u32, i32, u64, i64);
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<Answer, Box<dyn Error>> {
+        Ok(Answer::Text(self))
+    }
+}
+
+impl<T: IntoAnswer, E: Error + 'static> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<Answer, Box<dyn Error>> {
+        self?.into_answer()
+    }
+}
+
+// Solvers return `None` if the input has no solution.
+impl<T: IntoAnswer> IntoAnswer for Option<T> {
+    fn into_answer(self) -> Result<Answer, Box<dyn Error>> {
+        self.ok_or("no solution found")?.into_answer()
+    }
+}
+
+/// The parsed puzzle input, as returned by `Solver::parse()`.
+///
+/// The actual type depends on the day and the part, so it is opaque to the caller. It can only
+/// be passed back to the solver that created it.
+pub struct Input(Box<dyn Any>);
+
+/// A solver for the puzzle of a single day.
+pub trait Solver: Sync {
+    fn day(&self) -> u8;
+
+    /// Parses the puzzle input for the given part.
+    fn parse(&self, part: Part, input: &str) -> Result<Input, ParseError>;
+
+    /// Solves part 1, given the input that was parsed for part 1.
+    fn part1(&self, input: &Input) -> Result<Answer, Box<dyn Error>>;
+
+    /// Solves part 2, given the input that was parsed for part 2.
+    fn part2(&self, input: &Input) -> Result<Answer, Box<dyn Error>>;
+
+    /// Solves the given part, given the input that was parsed for it.
+    fn solve(&self, part: Part, input: &Input) -> Result<Answer, Box<dyn Error>> {
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input),
+        }
+    }
+
+    /// Parses the raw puzzle input and solves the given part.
+    fn run(&self, part: Part, input: &str) -> Result<Answer, Box<dyn Error>> {
+        let input = self.parse(part, input)?;
+        self.solve(part, &input)
+    }
+}
+
+// A generator and the solution function that consumes its output.
+trait PartSolver: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Input, ParseError>;
+    fn solve(&self, input: &Input) -> Result<Answer, Box<dyn Error>>;
+}
+
+// The solution functions take a borrowed version of the generated type, e.g. `&[T]` for a
+// `Vec<T>`, hence the separate `B` type.
+struct PartFns<I, B: ?Sized, A> {
+    parse: fn(&str) -> Result<I, ParseError>,
+    solve: fn(&B) -> A,
+}
+
+impl<I, B, A> PartSolver for PartFns<I, B, A>
+where
+    I: Borrow<B> + 'static,
+    B: ?Sized,
+    A: IntoAnswer,
+{
+    fn parse(&self, input: &str) -> Result<Input, ParseError> {
+        Ok(Input(Box::new((self.parse)(input)?)))
+    }
+
+    fn solve(&self, input: &Input) -> Result<Answer, Box<dyn Error>> {
+        let input = input
+            .0
+            .downcast_ref::<I>()
+            .ok_or("the input was parsed for a different day or part")?;
+        (self.solve)(input.borrow()).into_answer()
+    }
+}
+
+/// A solver built from the `#[aoc_generator]` and `#[aoc]` functions of a day module.
+pub struct DaySolver {
+    day: u8,
+    part1: Box<dyn PartSolver>,
+    part2: Box<dyn PartSolver>,
+}
+
+impl DaySolver {
+    pub(crate) fn new<I1, B1, A1, I2, B2, A2>(
+        day: u8,
+        parse1: fn(&str) -> Result<I1, ParseError>,
+        part1: fn(&B1) -> A1,
+        parse2: fn(&str) -> Result<I2, ParseError>,
+        part2: fn(&B2) -> A2,
+    ) -> Self
+    where
+        I1: Borrow<B1> + 'static,
+        B1: ?Sized + 'static,
+        A1: IntoAnswer + 'static,
+        I2: Borrow<B2> + 'static,
+        B2: ?Sized + 'static,
+        A2: IntoAnswer + 'static,
+    {
+        Self {
+            day,
+            part1: Box::new(PartFns {
+                parse: parse1,
+                solve: part1,
+            }),
+            part2: Box::new(PartFns {
+                parse: parse2,
+                solve: part2,
+            }),
+        }
+    }
+
+    fn part(&self, part: Part) -> &dyn PartSolver {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+impl Solver for DaySolver {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn parse(&self, part: Part, input: &str) -> Result<Input, ParseError> {
+        self.part(part).parse(input)
+    }
+
+    fn part1(&self, input: &Input) -> Result<Answer, Box<dyn Error>> {
+        self.part1.solve(input)
+    }
+
+    fn part2(&self, input: &Input) -> Result<Answer, Box<dyn Error>> {
+        self.part2.solve(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_solver, solvers};
+
+    #[test]
+    fn test_registry() {
+        let days = solvers().map(|s| s.day()).collect::<Vec<u8>>();
+        // Every day is registered once, in order.
+        assert_eq!((1..=25).collect::<Vec<u8>>(), days);
+        for day in days {
+            assert_eq!(Some(day), find_solver(day).map(|s| s.day()));
+        }
+        assert!(find_solver(0).is_none());
+        assert!(find_solver(26).is_none());
+    }
+
+    #[test]
+    fn test_run() {
+        let solver = find_solver(1).unwrap();
+        let input = "1000\n2000\n\n4000\n\n500\n";
+        assert_eq!(Answer::Integer(4000), solver.run(Part::One, input).unwrap());
+        assert_eq!(Answer::Integer(7500), solver.run(Part::Two, input).unwrap());
+        assert_eq!("7500", solver.run(Part::Two, input).unwrap().to_string());
+
+        let error = solver.run(Part::One, "1000\nabc\n").unwrap_err();
+        assert_eq!(
+            "day 1, line 2, column 1: invalid calorie count in \"abc\"",
+            error.to_string()
+        );
+
+        let input = solver.parse(Part::One, "1000\n").unwrap();
+        assert!(solvers().nth(1).unwrap().part1(&input).is_err());
+    }
+
+    #[test]
+    fn test_text_answer() {
+        let solver = find_solver(5).unwrap();
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\n";
+        assert_eq!(
+            Answer::Text("DCP".to_string()),
+            solver.run(Part::One, input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_optional_answer() {
+        assert_eq!(Answer::Integer(3), Some(3usize).into_answer().unwrap());
+        let error = None::<usize>.into_answer().unwrap_err();
+        assert_eq!("no solution found", error.to_string());
+    }
+}
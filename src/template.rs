@@ -1,68 +1,66 @@
+use crate::error::ParseError;
+use crate::input::normalise;
+use crate::solver::DaySolver;
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
 
-#[aoc_generator(day0, part1)]
-fn parse_input_part1(input: &str) -> Vec<String> {
-    input.lines().map(|line| line.to_string()).collect()
-}
-
-#[aoc_generator(day0, part2)]
-fn parse_input_part2(input: &str) -> Vec<String> {
-    input.lines().map(|line| line.to_string()).collect()
+#[aoc_generator(day0)]
+fn parse_input(input: &str) -> Result<Vec<String>, ParseError> {
+    let input = normalise(input);
+    Ok(input.lines().map(String::from).collect())
 }
 
 #[aoc(day0, part1)]
-fn part1(input: &[String]) -> usize {
+fn part1(_input: &[String]) -> usize {
     0
 }
 
 #[aoc(day0, part2)]
-fn part2(input: &[String]) -> usize {
+fn part2(_input: &[String]) -> usize {
     0
 }
 
+pub fn solver() -> DaySolver {
+    DaySolver::new(0, parse_input, part1, parse_input, part2)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::variants;
     use indoc::indoc;
 
     #[test]
-    fn test_parse_input_part1() {
-        let expected = vec![
-            "1abc2".to_string(),
-            "pqr3stu8vwx".to_string(),
-            "a1b2c3d4e5f".to_string(),
-            "treb7uchet".to_string(),
-        ];
+    fn test_parse_input() {
+        let expected: Vec<String> = get_test_input().lines().map(String::from).collect();
 
-        assert_eq!(expected, parse_input_part1(get_test_input_part1()));
+        assert_eq!(Ok(expected), parse_input(get_test_input()));
     }
 
     #[test]
-    fn test_parse_input_part2() {
-        test_parse_input_part1();
+    fn test_parse_input_variants() {
+        let expected = parse_input(get_test_input());
+        for input in variants(get_test_input()) {
+            assert_eq!(expected, parse_input(&input));
+        }
     }
 
     #[test]
+    #[ignore = "part 1 is not solved yet"]
     fn part1_example() {
-        let input = parse_input_part1(get_test_input_part1());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(0, part1(&input));
     }
 
     #[test]
+    #[ignore = "part 2 is not solved yet"]
     fn part2_example() {
-        let input = parse_input_part2(get_test_input_part2());
+        let input = parse_input(get_test_input()).unwrap();
         assert_eq!(0, part2(&input));
     }
 
-    fn get_test_input_part1<'a>() -> &'a str {
+    fn get_test_input<'a>() -> &'a str {
         indoc! {"
             example
-            input
         "}
     }
-
-    fn get_test_input_part2<'a>() -> &'a str {
-        get_test_input_part1()
-    }
 }
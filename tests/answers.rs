@@ -0,0 +1,91 @@
+use advent_of_code_2022::solver::Part;
+use advent_of_code_2022::solvers;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+// Checks the answers of all solvers against the puzzle inputs in `input/2022`, so refactorings
+// cannot silently change the results.
+#[test]
+fn answers() {
+    let (expected, missing) = get_expected_answers();
+    let mut failures = vec![];
+    let mut skipped = vec![];
+
+    for solver in solvers() {
+        let day = solver.day();
+        let path = get_input_dir().join(format!("day{}.txt", day));
+        let Ok(input) = fs::read_to_string(&path) else {
+            // Every registered day needs an input, unless it is known to be missing.
+            if missing.contains(&day) {
+                skipped.push(day.to_string());
+            } else {
+                failures.push(format!("Day {}: {} not found", day, path.display()));
+            }
+            continue;
+        };
+        if missing.contains(&day) {
+            failures.push(format!(
+                "Day {}: listed as missing, but {} exists",
+                day,
+                path.display()
+            ));
+        }
+
+        for part in Part::VALUES {
+            let result = match solver.run(part, &input) {
+                Ok(answer) => answer.to_string(),
+                Err(e) => format!("error: {}", e),
+            };
+            match expected.get(&(day, part.number())) {
+                Some(answer) if *answer == result => {}
+                Some(answer) => failures.push(format!(
+                    "Day {} - Part {}: expected {}, got {}",
+                    day, part, answer, result
+                )),
+                None => failures.push(format!(
+                    "Day {} - Part {}: no expected answer recorded, got {}",
+                    day, part, result
+                )),
+            }
+        }
+    }
+
+    if !skipped.is_empty() {
+        eprintln!("Skipped days without an input: {}", skipped.join(", "));
+    }
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n"));
+}
+
+fn get_input_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("input/2022")
+}
+
+// Reads the expected answers, keyed by day and part, and the days whose input is missing.
+fn get_expected_answers() -> (BTreeMap<(u8, u8), String>, BTreeSet<u8>) {
+    let path = get_input_dir().join("answers.txt");
+    let contents = fs::read_to_string(&path).unwrap();
+    let mut answers = BTreeMap::new();
+    let mut missing = BTreeSet::new();
+
+    for line in contents
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let fields = line.splitn(3, ' ').collect::<Vec<&str>>();
+        match fields[..] {
+            [day, "missing"] => {
+                missing.insert(day.parse().unwrap());
+            }
+            [day, part, answer] => {
+                answers.insert(
+                    (day.parse().unwrap(), part.parse().unwrap()),
+                    answer.to_string(),
+                );
+            }
+            _ => panic!("Invalid line in {}: {:?}", path.display(), line),
+        }
+    }
+
+    (answers, missing)
+}